@@ -1,6 +1,6 @@
 # This is a modified version of https://github.com/daemontus/artefact-aeon-py/blob/2f59d32acc8ac278f18a4cf6fa30e6b4b3c0c619/run.py
 # In particular, the changes are:
-#  - We don't invoke UNIX `time` explicitly. Instead we assume that the binary will print the time in a format that we can understand.
+#  - Instead of a script, we run a source-target control command of the `pbn-control` binary (`one-step`, `temporary` or `permanent`).
 #  - The benchmarks are taken from a two-level hierarchy of folders instead of a single directory (this influences the output as well).
 #  - 
 
@@ -28,8 +28,9 @@ if __name__ == "__main__":
     print("Timeout:", CUT_OFF)
     BENCH_DIR = sys.argv[2]
     print("Benchmark group directory:", BENCH_DIR)
-    SCRIPT = sys.argv[3]
-    print("Script:", SCRIPT)
+    MODE = sys.argv[3]
+    print("Control mode:", MODE)
+    SCRIPT = "./target/release/pbn-control " + MODE
     INTERACTIVE = False
     PARALLEL = 0
     if len(sys.argv) > 4:
@@ -64,18 +65,18 @@ if __name__ == "__main__":
         return benchmark.endswith(".aeon")
 
     # Create output directory
-    OUT_DIR = BENCH_DIR.replace("/", "_") + "_" + os.path.basename(SCRIPT)
+    OUT_DIR = BENCH_DIR.replace("/", "_") + "_" + MODE
     if PARALLEL > 0:
         OUT_DIR = OUT_DIR + "_parallel"
     OUT_DIR = "_run_" + OUT_DIR + "_" + str(int(time.time()))
     os.mkdir(OUT_DIR)
 
     # Create output stats file
-    TIMES = open(OUT_DIR + "/" + BENCH_DIR.replace("/", "_") + "_" + os.path.basename(SCRIPT) + "_times.csv", "w")
+    TIMES = open(OUT_DIR + "/" + BENCH_DIR.replace("/", "_") + "_" + MODE + "_times.csv", "w")
     TIMES.write("Benchmark, Time[s]\n")
 
     # Create an aggregated stats file
-    AGGREGATION = open(OUT_DIR + "/" + BENCH_DIR.replace("/", "_") + "_" + os.path.basename(SCRIPT) + "_aggregated.csv", "w")
+    AGGREGATION = open(OUT_DIR + "/" + BENCH_DIR.replace("/", "_") + "_" + MODE + "_aggregated.csv", "w")
     AGGREGATION.write("Time[s], No. Completed\n")
 
     # Here, save all runtimes.
@@ -92,9 +93,6 @@ if __name__ == "__main__":
         
     BENCHMARKS = sorted(BENCHMARKS)
 
-    if SCRIPT.endswith(".py"):
-        SCRIPT = "python3 " + SCRIPT
-
     # Handle data from a finished process. In particular,
     # update AGGREGATION_LIST and TIMES file.
     def PROCESS_RESULT(process, name, output_file):
@@ -138,7 +136,7 @@ if __name__ == "__main__":
                 name = os.path.splitext(bench)[0].replace("/", "_")
                 input_file = BENCH_DIR + "/" + bench
                 output_file = OUT_DIR + "/" + name + "_out.txt"
-                command = TIMEOUT + " " + CUT_OFF + " " + SCRIPT + " " + input_file + " > " + output_file + " 2>&1"
+                command = TIMEOUT + " " + CUT_OFF + " time -p " + SCRIPT + " --model " + quote(input_file) + " > " + output_file + " 2>&1"
                 process = Process(target=SPAWN, args=(command,))
                 process.start()
                 ACTIVE.append((process, name, output_file))
@@ -167,7 +165,7 @@ if __name__ == "__main__":
             print(name)
             input_file = BENCH_DIR + "/" + bench
             output_file = OUT_DIR + "/" + name + "_out.txt"
-            command = TIMEOUT + " " + CUT_OFF + " " + SCRIPT + " " + input_file + " > " + output_file + " 2>&1"
+            command = TIMEOUT + " " + CUT_OFF + " time -p " + SCRIPT + " --model " + quote(input_file) + " > " + output_file + " 2>&1"
             process = Process(target=SPAWN, args=(command,))
             process.start()
             process.join()
@@ -0,0 +1,207 @@
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use biodivine_lib_param_bn::{BooleanNetwork, FnUpdate, RegulatoryGraph, VariableId};
+use biodivine_pbn_control::aeon::config::ExperimentConfig;
+use biodivine_pbn_control::perturbation::PerturbationGraph;
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+/// Measure the symbolic size of the perturbation graph encoding for every model of
+/// the benchmark configuration (or only the models given as arguments), e.g.
+/// `cargo run --release --example encoding -- full_mapk`.
+///
+/// For comparison, the same is measured for the previous encoding, where the network has
+/// an artificial `v -? v` auto-regulation of every variable (see `self_loop_encoding`).
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let models = if args.is_empty() {
+        config.model_names()
+    } else {
+        args.iter().map(|it| it.as_str()).collect()
+    };
+
+    for model in models {
+        let model_config = config
+            .try_get_model(model)
+            .unwrap_or_else(|e| panic!("{}", e));
+        let bn = model_config.try_load_network().unwrap();
+        let controllable_vars = model_config
+            .try_controllable_variables(&bn)
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        let now = Instant::now();
+        let perturbation_graph =
+            PerturbationGraph::with_restricted_variables(&bn, &controllable_vars);
+        let construction = now.elapsed();
+        let (can_post_size, can_post_time) = can_post(perturbation_graph.as_perturbed());
+
+        println!(">>>>>>>>>>>>>> {}", model);
+        println!("Variables: {}", bn.num_vars());
+        println!("Controllable variables: {}", controllable_vars.len());
+        println!(
+            "Symbolic variables: {}",
+            perturbation_graph
+                .as_symbolic_context()
+                .bdd_variable_set()
+                .num_vars()
+        );
+        println!(
+            "Original update BDD nodes: {}",
+            update_size(perturbation_graph.as_original())
+        );
+        println!(
+            "Perturbed update BDD nodes: {}",
+            update_size(perturbation_graph.as_perturbed())
+        );
+        println!(
+            "Unit BDD nodes: {}",
+            perturbation_graph.unit_colored_vertices().symbolic_size()
+        );
+        println!("Can post BDD nodes: {}", can_post_size);
+        println!("Construction: {:?}", construction);
+        println!("Can post: {:?}", can_post_time);
+
+        let now = Instant::now();
+        match self_loop_encoding(&bn, &controllable_vars) {
+            Ok((original, perturbed)) => {
+                let construction = now.elapsed();
+                let (can_post_size, can_post_time) = can_post(&perturbed);
+                println!(
+                    "Self-loop symbolic variables: {}",
+                    original.symbolic_context().bdd_variable_set().num_vars()
+                );
+                println!(
+                    "Self-loop original update BDD nodes: {}",
+                    update_size(&original)
+                );
+                println!(
+                    "Self-loop perturbed update BDD nodes: {}",
+                    update_size(&perturbed)
+                );
+                println!(
+                    "Self-loop unit BDD nodes: {}",
+                    original.unit_colored_vertices().symbolic_size()
+                );
+                println!("Self-loop can post BDD nodes: {}", can_post_size);
+                println!("Self-loop construction: {:?}", construction);
+                println!("Self-loop can post: {:?}", can_post_time);
+            }
+            Err(e) => println!("Self-loop encoding failed: {}", e.replace('\n', " ")),
+        }
+    }
+}
+
+/// One symbolic step of every variable in the whole graph: the total size of the results
+/// and the time it took.
+fn can_post(graph: &SymbolicAsyncGraph) -> (usize, Duration) {
+    let now = Instant::now();
+    let size = graph
+        .variables()
+        .map(|it| {
+            graph
+                .var_can_post(it, graph.unit_colored_vertices())
+                .symbolic_size()
+        })
+        .sum();
+    (size, now.elapsed())
+}
+
+/// Build the original and perturbed graph using the previous encoding: every variable has
+/// a `v -? v` auto-regulation, and the perturbed update function
+/// `(v_perturbed => v) & (!v_perturbed => f)` is a regular update function of the perturbed
+/// network. The regulations keep their observability and monotonicity, such that the original
+/// graph has the same colors as in `PerturbationGraph`. Fails if the perturbed function
+/// violates the monotonicity of an existing auto-regulation.
+fn self_loop_encoding(
+    network: &BooleanNetwork,
+    perturb: &[VariableId],
+) -> Result<(SymbolicAsyncGraph, SymbolicAsyncGraph), String> {
+    let mut network = network.clone();
+    for var in network.variables() {
+        if network.get_update_function(var).is_none() {
+            // Replace implicit functions with explicit parameters.
+            let regulators = network
+                .regulators(var)
+                .into_iter()
+                .map(FnUpdate::mk_var)
+                .collect::<Vec<_>>();
+            let name = format!("update_{}", network.get_variable_name(var));
+            let arity = u32::try_from(regulators.len()).unwrap();
+            let parameter = network.add_parameter(name.as_str(), arity).unwrap();
+            network
+                .add_update_function(var, FnUpdate::Param(parameter, regulators))
+                .unwrap();
+        }
+    }
+
+    let mut graph = RegulatoryGraph::new(
+        network
+            .variables()
+            .map(|it| network.get_variable_name(it).clone())
+            .collect(),
+    );
+    for regulation in network.as_graph().regulations() {
+        graph
+            .add_regulation(
+                network.get_variable_name(regulation.get_regulator()),
+                network.get_variable_name(regulation.get_target()),
+                regulation.is_observable(),
+                regulation.get_monotonicity(),
+            )
+            .unwrap();
+    }
+    for var in graph.variables() {
+        if graph.find_regulation(var, var).is_none() {
+            let name = graph.get_variable_name(var).clone();
+            graph
+                .add_regulation(name.as_str(), name.as_str(), false, None)
+                .unwrap();
+        }
+    }
+
+    let mut original = BooleanNetwork::new(graph.clone());
+    let mut perturbed = BooleanNetwork::new(graph);
+    for p in network.parameters() {
+        let parameter = &network[p];
+        original
+            .add_parameter(parameter.get_name(), parameter.get_arity())
+            .unwrap();
+        perturbed
+            .add_parameter(parameter.get_name(), parameter.get_arity())
+            .unwrap();
+    }
+    for var in network.variables().rev() {
+        // Implicit functions were replaced by parameters above.
+        let function = network.get_update_function(var).clone().unwrap();
+        if perturb.contains(&var) {
+            let name = format!("{}_perturbed", network.get_variable_name(var));
+            let parameter = original.add_parameter(name.as_str(), 0).unwrap();
+            perturbed.add_parameter(name.as_str(), 0).unwrap();
+            let is_perturbed = || FnUpdate::mk_param(parameter, &[]);
+            // The original function has to contain the parameter to get the same encoding.
+            let tautology = is_perturbed().or(FnUpdate::mk_not(is_perturbed()));
+            let controlled = is_perturbed()
+                .implies(FnUpdate::mk_var(var))
+                .and(FnUpdate::mk_not(is_perturbed()).implies(function.clone()));
+            original
+                .add_update_function(var, tautology.and(function))
+                .unwrap();
+            perturbed.add_update_function(var, controlled).unwrap();
+        } else {
+            original.add_update_function(var, function.clone()).unwrap();
+            perturbed.add_update_function(var, function).unwrap();
+        }
+    }
+
+    Ok((
+        SymbolicAsyncGraph::new(&original)?,
+        SymbolicAsyncGraph::new(&perturbed)?,
+    ))
+}
+
+fn update_size(graph: &SymbolicAsyncGraph) -> usize {
+    graph
+        .variables()
+        .map(|it| graph.get_symbolic_fn_update(it).size())
+        .sum()
+}
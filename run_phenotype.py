@@ -1,6 +1,6 @@
 # This is a modified version of https://github.com/daemontus/artefact-aeon-py/blob/2f59d32acc8ac278f18a4cf6fa30e6b4b3c0c619/run.py
 # In particular, the changes are:
-#  - We don't invoke UNIX `time` explicitly. Instead we assume that the binary will print the time in a format that we can understand.
+#  - Instead of a script, we run the `phenotype-bounded` command of the `pbn-control` binary for the models of `models_phenotype/benchmark.json`.
 #  - The benchmarks are taken from a two-level hierarchy of folders instead of a single directory (this influences the output as well).
 #  -
 import sys
@@ -49,7 +49,7 @@ if __name__ == "__main__":
 
     # 4 days
     CUT_OFF = "96h"
-    SCRIPT = "./target/release/experiment_phenotype"
+    SCRIPT = "./target/release/pbn-control phenotype-bounded --config models_phenotype/benchmark.json"
     INTERACTIVE = False
     PARALLEL = 8
 
@@ -138,7 +138,7 @@ if __name__ == "__main__":
             bench = f"{model}_{phenotype}"
             # input_file = f"models_phenotype/{bench}"
             output_file = f"{OUT_DIR}/{bench}_out.txt"
-            command_body = SCRIPT + " " + model + " " + phenotype + " " + PERTURBATION_MAX_SIZE
+            command_body = SCRIPT + " --model " + model + " --phenotype " + phenotype + " --size-bound " + PERTURBATION_MAX_SIZE
             command = TIMEOUT + " " + CUT_OFF + " time -p " + " " + command_body + " > " + output_file + " 2>&1"
             process = Process(target=SPAWN, args=(command,))
             process.start()
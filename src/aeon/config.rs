@@ -1,54 +1,249 @@
-use crate::aeon::phentoype::build_phenotype;
-use crate::perturbation::PerturbationGraph;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphVertices;
+use crate::aeon::phentoype::PhenotypeExpression;
+use crate::error::ControlError;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphVertices, SymbolicAsyncGraph};
 use biodivine_lib_param_bn::{BooleanNetwork, VariableId};
+use serde_json::Value;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
 
-pub(crate) fn get_controllable_vars(
-    model_file: &str,
-    model_name: &str,
-    extra_forbidden: Vec<&str>,
-) -> Vec<VariableId> {
-    let model_string =
-        std::fs::read_to_string(format!("./models_phenotype/{}", model_file)).unwrap();
-    let bn = BooleanNetwork::try_from(model_string.as_str()).unwrap();
-
-    let config_str = std::fs::read_to_string("./models_phenotype/benchmark.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config_str.as_str()).unwrap();
-
-    let mut controllable_vars = Vec::new();
-    let uncontrollable = config[model_name]["uncontrollable"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    // let inputs = config[model_name]["inputs"].as_array().unwrap().into_iter().map(|x| x.as_str().unwrap()).collect::<Vec<&str>>();
-    for v in bn.variables() {
-        if !uncontrollable.contains(&bn.get_variable_name(v).as_str())
-            && !extra_forbidden.contains(&bn.get_variable_name(v).as_str())
-        {
-            controllable_vars.push(v);
+/// A benchmark configuration (e.g. `models_phenotype/benchmark.json`), which maps model keys
+/// to a `ModelConfig`.
+#[derive(Clone, Debug)]
+pub struct ExperimentConfig {
+    models: HashMap<String, ModelConfig>,
+}
+
+/// Configuration of a single benchmark model.
+#[derive(Clone, Debug)]
+pub struct ModelConfig {
+    /// Path to the model file. Relative paths are resolved against the directory of the
+    /// configuration file.
+    pub file: PathBuf,
+    /// Input variables of the model. These are not controllable.
+    pub inputs: Vec<String>,
+    /// Other variables which must not be perturbed.
+    pub uncontrollable: Vec<String>,
+    /// Named phenotypes (see `PhenotypeExpression::try_from_json`).
+    pub targets: HashMap<String, PhenotypeExpression>,
+}
+
+impl ExperimentConfig {
+    /// Read a configuration file. Variable names are only checked once a model is loaded
+    /// (see `ModelConfig::try_load_network`).
+    pub fn try_load<P: AsRef<Path>>(path: P) -> Result<ExperimentConfig, ControlError> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| ControlError::Io(format!("{}: {}", path.display(), e)))?;
+        let value: Value = serde_json::from_str(data.as_str())
+            .map_err(|e| ControlError::InvalidConfig(e.to_string()))?;
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        ExperimentConfig::try_from_json(&value, directory)
+    }
+
+    /// Read a configuration from JSON, resolving model files against `directory`.
+    pub fn try_from_json(
+        value: &Value,
+        directory: &Path,
+    ) -> Result<ExperimentConfig, ControlError> {
+        let models = value
+            .as_object()
+            .ok_or_else(|| ControlError::InvalidConfig("Expected an object of models.".into()))?
+            .iter()
+            .map(|(name, model)| {
+                ModelConfig::try_from_json(model, directory)
+                    .map(|model| (name.clone(), model))
+                    .map_err(|e| ControlError::InvalidConfig(format!("{}: {}", name, e)))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(ExperimentConfig { models })
+    }
+
+    /// Keys of all models in this configuration, in alphabetical order.
+    pub fn model_names(&self) -> Vec<&str> {
+        let mut names = self.models.keys().map(|it| it.as_str()).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    pub fn get_model(&self, name: &str) -> Option<&ModelConfig> {
+        self.models.get(name)
+    }
+
+    /// The same as `get_model`, but an unknown model is reported as an error.
+    pub fn try_get_model(&self, name: &str) -> Result<&ModelConfig, ControlError> {
+        self.get_model(name)
+            .ok_or_else(|| ControlError::InvalidConfig(format!("Unknown model {:?}.", name)))
+    }
+}
+
+impl ModelConfig {
+    fn try_from_json(value: &Value, directory: &Path) -> Result<ModelConfig, ControlError> {
+        let file = value["file"]
+            .as_str()
+            .ok_or_else(|| ControlError::InvalidConfig("Missing model file.".into()))?;
+        let mut targets = HashMap::new();
+        if let Some(values) = value["targets"].as_object() {
+            for (name, target) in values {
+                targets.insert(name.clone(), PhenotypeExpression::try_from_json(target)?);
+            }
         }
+        Ok(ModelConfig {
+            file: directory.join(file),
+            inputs: read_names(&value["inputs"], "inputs")?,
+            uncontrollable: read_names(&value["uncontrollable"], "uncontrollable")?,
+            targets,
+        })
     }
 
-    return controllable_vars;
+    /// Read the model file and check that it contains all variables used by this configuration.
+    pub fn try_load_network(&self) -> Result<BooleanNetwork, ControlError> {
+        let data = std::fs::read_to_string(&self.file)
+            .map_err(|e| ControlError::Io(format!("{}: {}", self.file.display(), e)))?;
+        let network =
+            BooleanNetwork::try_from(data.as_str()).map_err(ControlError::InvalidNetwork)?;
+        self.try_validate(&network)?;
+        Ok(network)
+    }
+
+    /// Check that all inputs, uncontrollable variables and target variables appear in
+    /// `network`. The network does not have to be loaded from `ModelConfig::file`
+    /// (e.g. it can be a variant of the model with different update functions).
+    pub fn try_validate(&self, network: &BooleanNetwork) -> Result<(), ControlError> {
+        let mut names = self.inputs.iter().chain(self.uncontrollable.iter());
+        if let Some(name) = names.find(|it| network.as_graph().find_variable(it).is_none()) {
+            return Err(ControlError::UnknownVariable(name.clone()));
+        }
+        let mut targets = self.targets.iter().collect::<Vec<_>>();
+        targets.sort_by_key(|(name, _)| name.as_str());
+        for (target, phenotype) in targets {
+            if let Some(name) = phenotype
+                .variables()
+                .into_iter()
+                .find(|it| network.as_graph().find_variable(it).is_none())
+            {
+                return Err(ControlError::InvalidConfig(format!(
+                    "Unknown variable {:?} in target {:?}.",
+                    name, target
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Variables of `network` that are neither inputs, nor uncontrollable.
+    pub fn try_controllable_variables(
+        &self,
+        network: &BooleanNetwork,
+    ) -> Result<Vec<VariableId>, ControlError> {
+        self.try_validate(network)?;
+        Ok(network
+            .variables()
+            .filter(|it| {
+                let name = network.get_variable_name(*it);
+                !self.inputs.contains(name) && !self.uncontrollable.contains(name)
+            })
+            .collect())
+    }
+
+    /// The target with the given name. Other names are parsed as a phenotype formula.
+    pub fn try_phenotype_expression(
+        &self,
+        name: &str,
+    ) -> Result<PhenotypeExpression, ControlError> {
+        match self.targets.get(name) {
+            Some(phenotype) => Ok(phenotype.clone()),
+            None => PhenotypeExpression::try_parse(name),
+        }
+    }
+
+    /// States of the phenotype given by `ModelConfig::try_phenotype_expression`.
+    pub fn try_phenotype(
+        &self,
+        name: &str,
+        graph: &SymbolicAsyncGraph,
+    ) -> Result<GraphVertices, ControlError> {
+        self.try_phenotype_expression(name)?.try_build(graph)
+    }
+}
+
+/// Read an optional list of variable names.
+fn read_names(value: &Value, key: &str) -> Result<Vec<String>, ControlError> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(items) => items
+            .iter()
+            .map(|it| {
+                it.as_str().map(|it| it.to_string()).ok_or_else(|| {
+                    ControlError::InvalidConfig(format!("Expected variable names in {:?}.", key))
+                })
+            })
+            .collect(),
+        _ => Err(ControlError::InvalidConfig(format!(
+            "Expected a list of variable names in {:?}.",
+            key
+        ))),
+    }
 }
 
-pub(crate) fn get_trivial_phenotype(
-    model_name: &str,
-    phenotype_name: &str,
-    stg: &PerturbationGraph,
-) -> GraphVertices {
-    let config_str = std::fs::read_to_string("./models_phenotype/benchmark.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config_str.as_str()).unwrap();
-    let phenotype_map = config[model_name]["targets"][phenotype_name]
-        .as_object()
-        .unwrap();
-    let mut phenotype_vals = HashMap::new();
-    for (k, v) in phenotype_map {
-        phenotype_vals.insert(k.as_str(), v.as_bool().unwrap());
-    }
-    println!("{:?}", phenotype_vals);
-    build_phenotype(stg.as_perturbed(), phenotype_vals)
+#[cfg(test)]
+mod tests {
+    use crate::aeon::config::ExperimentConfig;
+    use crate::error::ControlError;
+    use crate::perturbation::PerturbationGraph;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    pub fn test_benchmark_config() {
+        let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+        for name in config.model_names() {
+            let model = config.get_model(name).unwrap();
+            model
+                .try_load_network()
+                .unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+
+        let cardiac = config.try_get_model("cardiac").unwrap();
+        let network = cardiac.try_load_network().unwrap();
+        let controllable = cardiac.try_controllable_variables(&network).unwrap();
+        assert_eq!(network.num_vars() - 4, controllable.len());
+
+        let graph = PerturbationGraph::with_restricted_variables(&network, &controllable);
+        let fhf = cardiac.try_phenotype("FHF", graph.as_perturbed()).unwrap();
+        let formula = cardiac
+            .try_phenotype("v_Tbx5 & !v_Tbx1", graph.as_perturbed())
+            .unwrap();
+        assert!(fhf.approx_cardinality() > 0.0);
+        assert!(formula.approx_cardinality() > 0.0);
+        assert!(config.try_get_model("unknown").is_err());
+    }
+
+    #[test]
+    pub fn test_invalid_config() {
+        let value = json!({
+            "myeloid": {
+                "file": "myeloid_witness.aeon",
+                "uncontrollable": ["EKLF"],
+                "targets": { "erythrocyte": { "EKLF": true, "Unknown": false } }
+            }
+        });
+        let config = ExperimentConfig::try_from_json(&value, Path::new("models")).unwrap();
+        let model = config.get_model("myeloid").unwrap();
+        assert!(model.inputs.is_empty());
+        assert!(matches!(
+            model.try_load_network(),
+            Err(ControlError::InvalidConfig(_))
+        ));
+
+        let value = json!({ "myeloid": { "file": "myeloid_witness.aeon", "inputs": ["X"] } });
+        let config = ExperimentConfig::try_from_json(&value, Path::new("models")).unwrap();
+        assert!(matches!(
+            config.get_model("myeloid").unwrap().try_load_network(),
+            Err(ControlError::UnknownVariable(name)) if name == "X"
+        ));
+
+        let value = json!({ "myeloid": { "inputs": ["EKLF"] } });
+        assert!(ExperimentConfig::try_from_json(&value, Path::new("models")).is_err());
+    }
 }
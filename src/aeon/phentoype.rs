@@ -1,32 +1,289 @@
+use crate::error::ControlError;
+use biodivine_lib_bdd::boolean_expression::BooleanExpression;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::symbolic_async_graph::{GraphVertices, SymbolicAsyncGraph};
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphVertices, SymbolicAsyncGraph,
+};
 use biodivine_lib_param_bn::VariableId;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-// Obtain subspace from human-readable variable names and their expected values (as conjunction)
+/// A phenotype given as a Boolean formula over variable names, e.g. `A & !(B & C)`.
+///
+/// Supports the usual operators `!`, `&`, `|`, `^`, `=>`, `<=>` and the constants
+/// `true`/`false`. Use `PhenotypeExpression::try_build` to obtain the phenotype states, which
+/// can then be passed to any phenotype control method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhenotypeExpression {
+    expression: BooleanExpression,
+}
+
+/// Panicking variant of `try_build_phenotype`.
 pub fn build_phenotype(
     graph: &SymbolicAsyncGraph,
     phenotype: HashMap<&str, bool>,
 ) -> GraphVertices {
+    try_build_phenotype(graph, phenotype).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Obtain the subspace given by human-readable variable names and their expected values
+/// (as a conjunction).
+///
+/// Returns an error if a variable name is not known.
+pub fn try_build_phenotype(
+    graph: &SymbolicAsyncGraph,
+    phenotype: HashMap<&str, bool>,
+) -> Result<GraphVertices, ControlError> {
     let mut result = graph.unit_colored_vertices().clone();
     for (var, value) in phenotype {
-        let var_id = resolve_var_id(graph, var).unwrap();
+        let var_id = try_resolve_var_id(graph, var)?;
         let subspace = graph.fix_network_variable(var_id, value);
         result = result.intersect(&subspace);
     }
 
-    result.vertices().clone()
+    Ok(result.vertices())
 }
 
 pub fn resolve_var_id(graph: &SymbolicAsyncGraph, var: &str) -> Option<VariableId> {
-    let mut v_name = String::new();
-    for v in graph.variables() {
-        // Resolve variable name
-        v_name = graph.get_variable_name(v);
-        if var == v_name {
-            return Some(v);
+    graph
+        .variables()
+        .find(|v| graph.get_variable_name(*v) == var)
+}
+
+/// The same as `resolve_var_id`, but an unknown name is reported as an error.
+pub fn try_resolve_var_id(
+    graph: &SymbolicAsyncGraph,
+    var: &str,
+) -> Result<VariableId, ControlError> {
+    resolve_var_id(graph, var).ok_or_else(|| ControlError::UnknownVariable(var.to_string()))
+}
+
+impl PhenotypeExpression {
+    /// Parse a phenotype formula. Variable names are only checked once the expression is built.
+    pub fn try_parse(formula: &str) -> Result<PhenotypeExpression, ControlError> {
+        BooleanExpression::try_from(formula)
+            .map(|expression| PhenotypeExpression { expression })
+            .map_err(|e| ControlError::InvalidPhenotype(format!("{:?}: {}", formula, e)))
+    }
+
+    /// A phenotype which is a conjunction of variable values (the same as `build_phenotype`).
+    pub fn from_subspace(phenotype: &HashMap<&str, bool>) -> PhenotypeExpression {
+        let mut literals = phenotype.iter().collect::<Vec<_>>();
+        // Sort to make the expression deterministic.
+        literals.sort();
+        let expression = literals
+            .into_iter()
+            .map(|(name, value)| {
+                let literal = BooleanExpression::Variable(name.to_string());
+                if *value {
+                    literal
+                } else {
+                    BooleanExpression::Not(Box::new(literal))
+                }
+            })
+            .reduce(|a, b| BooleanExpression::And(Box::new(a), Box::new(b)))
+            .unwrap_or(BooleanExpression::Const(true));
+        PhenotypeExpression { expression }
+    }
+
+    /// A phenotype satisfied by states satisfying at least one of the given `phenotypes`.
+    /// An empty list gives an empty phenotype.
+    pub fn any_of(phenotypes: Vec<PhenotypeExpression>) -> PhenotypeExpression {
+        let expression = phenotypes
+            .into_iter()
+            .map(|it| it.expression)
+            .reduce(|a, b| BooleanExpression::Or(Box::new(a), Box::new(b)))
+            .unwrap_or(BooleanExpression::Const(false));
+        PhenotypeExpression { expression }
+    }
+
+    /// Read a phenotype from the `targets` section of a benchmark configuration. The
+    /// phenotype is either an object of variable values (a conjunction), a formula string,
+    /// or an array of such phenotypes (a disjunction).
+    pub fn try_from_json(value: &serde_json::Value) -> Result<PhenotypeExpression, ControlError> {
+        match value {
+            serde_json::Value::String(formula) => PhenotypeExpression::try_parse(formula),
+            serde_json::Value::Object(values) => {
+                let mut subspace = HashMap::new();
+                for (name, value) in values {
+                    let value = value.as_bool().ok_or_else(|| {
+                        ControlError::InvalidPhenotype(format!(
+                            "Value of {:?} is not a Boolean",
+                            name
+                        ))
+                    })?;
+                    subspace.insert(name.as_str(), value);
+                }
+                Ok(PhenotypeExpression::from_subspace(&subspace))
+            }
+            serde_json::Value::Array(items) => {
+                let phenotypes = items
+                    .iter()
+                    .map(PhenotypeExpression::try_from_json)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PhenotypeExpression::any_of(phenotypes))
+            }
+            _ => Err(ControlError::InvalidPhenotype(format!(
+                "Expected object, string or array, found {}",
+                value
+            ))),
+        }
+    }
+
+    /// Names of the variables used in this phenotype (sorted, without duplicates).
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables = Vec::new();
+        collect_variables(&self.expression, &mut variables);
+        variables.sort();
+        variables.dedup();
+        variables
+    }
+
+    /// Build the set of vertices that satisfy this phenotype in the given `graph`. Returns
+    /// an error if a variable name is not known.
+    pub fn try_build(&self, graph: &SymbolicAsyncGraph) -> Result<GraphVertices, ControlError> {
+        Ok(build_expression(graph, &self.expression)?.vertices())
+    }
+}
+
+impl FromStr for PhenotypeExpression {
+    type Err = ControlError;
+
+    fn from_str(formula: &str) -> Result<Self, Self::Err> {
+        PhenotypeExpression::try_parse(formula)
+    }
+}
+
+impl Display for PhenotypeExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+fn collect_variables(expression: &BooleanExpression, variables: &mut Vec<String>) {
+    match expression {
+        BooleanExpression::Const(_) => {}
+        BooleanExpression::Variable(name) => variables.push(name.clone()),
+        BooleanExpression::Not(inner) => collect_variables(inner, variables),
+        BooleanExpression::And(a, b)
+        | BooleanExpression::Or(a, b)
+        | BooleanExpression::Xor(a, b)
+        | BooleanExpression::Imp(a, b)
+        | BooleanExpression::Iff(a, b) => {
+            collect_variables(a, variables);
+            collect_variables(b, variables);
+        }
+        BooleanExpression::Cond(condition, then_expr, else_expr) => {
+            collect_variables(condition, variables);
+            collect_variables(then_expr, variables);
+            collect_variables(else_expr, variables);
+        }
+    }
+}
+
+fn build_expression(
+    graph: &SymbolicAsyncGraph,
+    expression: &BooleanExpression,
+) -> Result<GraphColoredVertices, ControlError> {
+    let unit = graph.unit_colored_vertices();
+    Ok(match expression {
+        BooleanExpression::Const(true) => unit.clone(),
+        BooleanExpression::Const(false) => graph.mk_empty_colored_vertices(),
+        BooleanExpression::Variable(name) => {
+            graph.fix_network_variable(try_resolve_var_id(graph, name)?, true)
+        }
+        BooleanExpression::Not(inner) => unit.minus(&build_expression(graph, inner)?),
+        BooleanExpression::And(a, b) => {
+            build_expression(graph, a)?.intersect(&build_expression(graph, b)?)
         }
+        BooleanExpression::Or(a, b) => {
+            build_expression(graph, a)?.union(&build_expression(graph, b)?)
+        }
+        BooleanExpression::Xor(a, b) => {
+            let (a, b) = (build_expression(graph, a)?, build_expression(graph, b)?);
+            a.minus(&b).union(&b.minus(&a))
+        }
+        BooleanExpression::Imp(a, b) => unit
+            .minus(&build_expression(graph, a)?)
+            .union(&build_expression(graph, b)?),
+        BooleanExpression::Iff(a, b) => {
+            let (a, b) = (build_expression(graph, a)?, build_expression(graph, b)?);
+            unit.minus(&a.minus(&b).union(&b.minus(&a)))
+        }
+        BooleanExpression::Cond(condition, then_expr, else_expr) => {
+            let condition = build_expression(graph, condition)?;
+            condition
+                .intersect(&build_expression(graph, then_expr)?)
+                .union(
+                    &unit
+                        .minus(&condition)
+                        .intersect(&build_expression(graph, else_expr)?),
+                )
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aeon::phentoype::{build_phenotype, PhenotypeExpression};
+    use crate::error::ControlError;
+    use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::AttractorSearch;
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_phenotype_expression_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let graph = perturbations.as_perturbed();
+
+        let eklf = erythrocyte_phenotype(&perturbations);
+        let gata1 = build_phenotype(graph, HashMap::from([("GATA1", true)]));
+        let either = PhenotypeExpression::try_parse("EKLF | GATA1").unwrap();
+        assert_eq!(either.try_build(graph).unwrap(), eklf.union(&gata1));
+
+        // A conjunction behaves the same as `build_phenotype`.
+        let subspace = HashMap::from([("EKLF", true), ("Fli1", false)]);
+        assert_eq!(
+            PhenotypeExpression::from_subspace(&subspace)
+                .try_build(graph)
+                .unwrap(),
+            build_phenotype(graph, subspace)
+        );
+
+        // "A and not (B and C)"
+        let both = build_phenotype(graph, HashMap::from([("GATA1", true), ("Fli1", true)]));
+        let expression = PhenotypeExpression::try_parse("EKLF & !(GATA1 & Fli1)").unwrap();
+        assert_eq!(expression.try_build(graph).unwrap(), eklf.minus(&both));
+
+        let json = serde_json::json!([{"EKLF": true}, "GATA1"]);
+        let from_json = PhenotypeExpression::try_from_json(&json).unwrap();
+        assert_eq!(
+            from_json.try_build(graph).unwrap(),
+            either.try_build(graph).unwrap()
+        );
+
+        assert!(matches!(
+            PhenotypeExpression::try_parse("EKLF & (GATA1"),
+            Err(ControlError::InvalidPhenotype(_))
+        ));
+        assert_eq!(
+            PhenotypeExpression::try_parse("EKLF | Foo")
+                .unwrap()
+                .try_build(graph),
+            Err(ControlError::UnknownVariable("Foo".to_string()))
+        );
+
+        // The expression can be used directly for phenotype control.
+        let control = perturbations.phenotype_permanent_control(
+            either.try_build(graph).unwrap(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Sinks,
+        );
+        let working =
+            control.perturbation_working_colors(&HashMap::from([(String::from("GATA1"), true)]));
+        assert_eq!(1.0, working.approx_cardinality());
     }
-    assert_eq!(var, v_name, "Unknown variable");
-    None
 }
@@ -23,7 +23,7 @@ pub fn backward_within(
             }
         }
         if cfg!(feature = "print_progress") && result.as_bdd().size() > 100_000 {
-            println!("Backward progress: {}", result.as_bdd().size())
+            eprintln!("Backward progress: {}", result.as_bdd().size())
         }
         if stop {
             return result;
@@ -51,7 +51,7 @@ pub fn backward(
             }
         }
         if cfg!(feature = "print_progress") && result.as_bdd().size() > 100_000 {
-            println!("Backward progress: {}", result.as_bdd().size())
+            eprintln!("Backward progress: {}", result.as_bdd().size())
         }
         if stop {
             return result;
@@ -76,7 +76,7 @@ pub fn forward(graph: &SymbolicAsyncGraph, initial: &GraphColoredVertices) -> Gr
             }
         }
         if cfg!(feature = "print_progress") && result.as_bdd().size() > 100_000 {
-            println!("Forward progress: {}", result.as_bdd().size())
+            eprintln!("Forward progress: {}", result.as_bdd().size())
         }
         if stop {
             return result;
@@ -108,7 +108,7 @@ pub fn forward_within(
             }
         }
         if cfg!(feature = "print_progress") && result.as_bdd().size() > 100_000 {
-            println!("Forward progress: {}", result.as_bdd().size())
+            eprintln!("Forward progress: {}", result.as_bdd().size())
         }
         if stop {
             return result;
@@ -139,7 +139,7 @@ pub fn forward_closed_within(
             }
         }
         if cfg!(feature = "print_progress") && result.as_bdd().size() > 100_000 {
-            println!("Forward-closed-within progress: {}", result.as_bdd().size())
+            eprintln!("Forward-closed-within progress: {}", result.as_bdd().size())
         }
         if stop {
             return result;
@@ -197,7 +197,37 @@ pub fn forward_closed(
             }
         }
         if cfg!(feature = "print_progress") && basin.as_bdd().size() > 100_000 {
-            println!("Forward closed progress: {}", basin.as_bdd().size())
+            eprintln!("Forward closed progress: {}", basin.as_bdd().size())
+        }
+        if stop {
+            return basin;
+        }
+    }
+}
+
+/// The same as `forward_closed`, but the states in `absorbing` are never removed. That is, the
+/// paths stop once they reach `absorbing`.
+///
+/// In particular, if the initial set is a weak basin of `absorbing`, the result is a strong
+/// basin of `absorbing` where the paths cannot leave it.
+pub fn forward_closed_until(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    absorbing: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let mut basin = initial.clone();
+    loop {
+        let mut stop = true;
+        for var in graph.variables().rev() {
+            let can_go_out = graph.var_can_post_out(var, &basin).minus(absorbing);
+            if !can_go_out.is_empty() {
+                basin = basin.minus(&can_go_out);
+                stop = false;
+                break;
+            }
+        }
+        if cfg!(feature = "print_progress") && basin.as_bdd().size() > 100_000 {
+            eprintln!("Forward closed until progress: {}", basin.as_bdd().size())
         }
         if stop {
             return basin;
@@ -0,0 +1,641 @@
+use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::fixed_points::FixedPoints;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColors, GraphVertices, SymbolicAsyncGraph,
+};
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_pbn_control::aeon::config::ExperimentConfig;
+use biodivine_pbn_control::aeon::phentoype::PhenotypeExpression;
+use biodivine_pbn_control::control::{ControlMap, ControlMode};
+use biodivine_pbn_control::error::ControlError;
+use biodivine_pbn_control::experiment_utils::{
+    get_all_params_with_attractor, try_parse_experiment, try_string_to_state,
+};
+use biodivine_pbn_control::perturbation::{PerturbationGraph, SourceQuantifier};
+use biodivine_pbn_control::phenotype_control::_simplified_algorithm::{
+    bounded_phenotype_control, bounded_phenotype_control_with,
+};
+use biodivine_pbn_control::phenotype_control::{
+    AttractorSearch, PerturbationCosts, PhenotypeControlMap,
+};
+use biodivine_pbn_control::report::ControlReport;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+use std::time::Instant;
+
+fn main() {
+    let matches = cli().get_matches();
+    let result = match matches.subcommand() {
+        Some(("attractors", args)) => run_attractors(args),
+        Some(("one-step", args)) => run_source_target_control(
+            args,
+            ControlMode::OneStep,
+            PerturbationGraph::one_step_control,
+        ),
+        Some(("temporary", args)) => run_source_target_control(
+            args,
+            ControlMode::Temporary,
+            PerturbationGraph::temporary_control,
+        ),
+        Some(("permanent", args)) => run_source_target_control(
+            args,
+            ControlMode::Permanent,
+            PerturbationGraph::permanent_control,
+        ),
+        Some(("sequential", args)) => run_sequential_control(args),
+        Some(("phenotype", args)) => run_phenotype_control(args),
+        Some(("phenotype-bounded", args)) => run_bounded_phenotype_control(args),
+        Some(("enumerate", args)) => run_enumerate(args),
+        _ => unreachable!("Subcommand is required."),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn cli() -> Command {
+    let source_target = |name: &'static str, about: &'static str| {
+        Command::new(name)
+            .about(about)
+            .arg(model_arg())
+            .arg(Arg::new("source").long("source").value_name("STATE").help(
+                "Source state, e.g. \"[True, False]\" (default: the `#source:` line of the model)",
+            ))
+            .arg(Arg::new("target").long("target").value_name("STATE").help(
+                "Target state, e.g. \"[True, False]\" (default: the `#target:` line of the model)",
+            ))
+            .args(variables_args())
+            .arg(size_bound_arg())
+            .arg(limit_arg())
+            .args(output_args())
+    };
+    Command::new("pbn-control")
+        .about("Control of parametrised Boolean networks.")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("attractors")
+                .about("Compute the attractors of the model")
+                .arg(model_arg())
+                .arg(
+                    Arg::new("attractors")
+                        .long("attractors")
+                        .value_parser(["sinks", "complex"])
+                        .default_value("complex")
+                        .help("Only compute fixed points (sinks), or all attractors"),
+                )
+                .args(output_args()),
+        )
+        .subcommand(
+            source_target(
+                "one-step",
+                "Source-target control with perturbations applied for a single step",
+            )
+            .arg(save_map_arg()),
+        )
+        .subcommand(
+            source_target(
+                "temporary",
+                "Source-target control with perturbations held as long as necessary",
+            )
+            .arg(save_map_arg()),
+        )
+        .subcommand(
+            source_target(
+                "permanent",
+                "Source-target control with perturbations that are never released",
+            )
+            .arg(save_map_arg()),
+        )
+        .subcommand(
+            source_target(
+                "sequential",
+                "Source-target control with a schedule of perturbations, where each \
+                perturbation is held until the next condition is reached",
+            )
+            .arg(
+                Arg::new("condition")
+                    .long("condition")
+                    .value_name("FORMULA")
+                    .action(ArgAction::Append)
+                    .help(
+                        "Condition which ends a phase of the schedule, as a Boolean formula \
+                        over variable names (repeat for more phases, in the order in which \
+                        they are reached)",
+                    ),
+            ),
+        )
+        .subcommand(
+            Command::new("phenotype")
+                .about("Phenotype control with perturbations up to the size bound")
+                .arg(model_arg())
+                .arg(phenotype_arg())
+                .arg(config_arg())
+                .args(variables_args())
+                .arg(size_bound_arg())
+                .arg(
+                    Arg::new("mode")
+                        .long("mode")
+                        .value_parser(["one-step", "temporary", "permanent"])
+                        .default_value("permanent")
+                        .help("How long the perturbation is applied"),
+                )
+                .arg(Arg::new("source").long("source").value_name("STATE").help(
+                    "Source state of `--mode one-step`, e.g. \"[True, False]\" \
+                    (default: the perturbation must work from every state)",
+                ))
+                .arg(attractors_arg().default_value("heuristic"))
+                .arg(limit_arg())
+                .arg(save_map_arg())
+                .args(output_args()),
+        )
+        .subcommand(
+            Command::new("phenotype-bounded")
+                .about("Permanent phenotype control for every perturbation size up to the bound")
+                .arg(model_arg())
+                .arg(phenotype_arg())
+                .arg(config_arg())
+                .args(variables_args())
+                .arg(size_bound_arg())
+                .arg(attractors_arg().help(
+                    "Attractor search used to find phenotype-violating attractors \
+                    (default: every trap set outside of the phenotype is violating)",
+                ))
+                .args(output_args()),
+        )
+        .subcommand(
+            Command::new("enumerate")
+                .about("Enumerate perturbations of a control map saved using `--save-map`")
+                .arg(
+                    Arg::new("map")
+                        .long("map")
+                        .value_name("PATH")
+                        .required(true)
+                        .help("Path to the saved control map"),
+                )
+                .arg(size_bound_arg())
+                .arg(limit_arg())
+                .args(output_args()),
+        )
+}
+
+fn model_arg() -> Arg {
+    Arg::new("model")
+        .long("model")
+        .short('m')
+        .value_name("PATH")
+        .required(true)
+        .help("Path to the model in the .aeon format")
+}
+
+fn phenotype_arg() -> Arg {
+    Arg::new("phenotype")
+        .long("phenotype")
+        .value_name("FORMULA")
+        .required(true)
+        .help("Phenotype as a Boolean formula over variable names, e.g. \"A & !(B | C)\"")
+}
+
+fn config_arg() -> Arg {
+    Arg::new("config").long("config").value_name("PATH").help(
+        "Benchmark configuration (e.g. models_phenotype/benchmark.json). The `--model` is \
+            then the name of a configured model, `--phenotype` can be the name of one of its \
+            targets and only its controllable variables are perturbable by default",
+    )
+}
+
+fn variables_args() -> [Arg; 2] {
+    [
+        Arg::new("perturbable")
+            .long("perturbable")
+            .value_name("NAMES")
+            .value_delimiter(',')
+            .help("Comma-separated variables which can be perturbed (default: all)"),
+        Arg::new("uncontrollable")
+            .long("uncontrollable")
+            .value_name("NAMES")
+            .value_delimiter(',')
+            .help("Comma-separated variables which must not be perturbed"),
+    ]
+}
+
+fn size_bound_arg() -> Arg {
+    Arg::new("size-bound")
+        .long("size-bound")
+        .value_name("N")
+        .value_parser(value_parser!(usize))
+        .default_value("1")
+        .help("Maximal number of perturbed variables")
+}
+
+fn limit_arg() -> Arg {
+    Arg::new("limit")
+        .long("limit")
+        .value_name("K")
+        .value_parser(value_parser!(usize))
+        .default_value("20")
+        .help(
+            "Maximal number of reported perturbations (the most robust ones for one-step \
+            and source-target control, the cheapest ones otherwise)",
+        )
+}
+
+fn attractors_arg() -> Arg {
+    Arg::new("attractors")
+        .long("attractors")
+        .value_parser(["sinks", "complex", "heuristic"])
+        .help("Attractor search used to find phenotype-violating attractors")
+}
+
+fn save_map_arg() -> Arg {
+    Arg::new("save-map")
+        .long("save-map")
+        .value_name("PATH")
+        .help("Save the control map, such that it can be used by `enumerate`")
+}
+
+fn output_args() -> [Arg; 2] {
+    [
+        Arg::new("format")
+            .long("format")
+            .value_parser(["text", "json"])
+            .default_value("text")
+            .help("Output format"),
+        Arg::new("output")
+            .long("output")
+            .short('o')
+            .value_name("PATH")
+            .help("Write the result into a file instead of the standard output"),
+    ]
+}
+
+fn run_attractors(args: &ArgMatches) -> Result<(), ControlError> {
+    let model_path = args.get_one::<String>("model").unwrap();
+    let network = read_model(model_path)?;
+    let graph = SymbolicAsyncGraph::new(&network).map_err(ControlError::InvalidNetwork)?;
+    let attractors = if args.get_one::<String>("attractors").unwrap() == "sinks" {
+        vec![FixedPoints::symbolic(&graph, graph.unit_colored_vertices())]
+    } else {
+        biodivine_pbn_control::aeon::attractors::compute(&graph)
+    };
+
+    let attractors = attractors
+        .iter()
+        .filter(|it| !it.is_empty())
+        .map(|it| {
+            json!({
+                "states": it.vertices().approx_cardinality(),
+                "colors": it.colors().approx_cardinality(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let text = attractors
+        .iter()
+        .enumerate()
+        .map(|(i, it)| {
+            format!(
+                "Attractor {}: {} state(s), {} color(s)\n",
+                i, it["states"], it["colors"]
+            )
+        })
+        .collect::<String>();
+    let value = json!({ "model": model_path, "attractors": attractors });
+    write_output(args, text, &value)
+}
+
+fn run_source_target_control(
+    args: &ArgMatches,
+    mode: ControlMode,
+    control: fn(&PerturbationGraph, &ArrayBitVector, &ArrayBitVector, &GraphColors) -> ControlMap,
+) -> Result<(), ControlError> {
+    let model_path = args.get_one::<String>("model").unwrap();
+    let (source, target, network) = read_source_target(args)?;
+    let graph = mk_perturbation_graph(&network, args, None)?;
+
+    let start = Instant::now();
+    // Colors where the target is not an attractor cannot be controlled, so we skip them.
+    let attractor_colors = get_all_params_with_attractor(&graph, &target);
+    let control = control(&graph, &source, &target, &attractor_colors);
+    let elapsed = start.elapsed();
+    if let Some(path) = args.get_one::<String>("save-map") {
+        control.save(path)?;
+    }
+
+    let size_bound = *args.get_one::<usize>("size-bound").unwrap();
+    let limit = *args.get_one::<usize>("limit").unwrap();
+    let mut report = ControlReport::new(model_path, &state_to_string(&target), mode);
+    report.add_control_perturbations(&control.top_k_by_robustness(limit, size_bound));
+    report.add_timing("control map", elapsed);
+    write_report(args, &report)
+}
+
+fn run_sequential_control(args: &ArgMatches) -> Result<(), ControlError> {
+    let model_path = args.get_one::<String>("model").unwrap();
+    let (source, target, network) = read_source_target(args)?;
+    let graph = mk_perturbation_graph(&network, args, None)?;
+    let mut conditions = Vec::new();
+    for formula in args.get_many::<String>("condition").unwrap_or_default() {
+        let condition = PhenotypeExpression::try_parse(formula)?.try_build(graph.as_perturbed())?;
+        conditions.push(
+            graph
+                .mk_unit_colored_vertices()
+                .intersect_vertices(&condition),
+        );
+    }
+
+    let start = Instant::now();
+    // Colors where the target is not an attractor cannot be controlled, so we skip them.
+    let attractor_colors = get_all_params_with_attractor(&graph, &target);
+    let control = graph.sequential_control(
+        &source,
+        &conditions,
+        &graph.vertex(&target),
+        &attractor_colors,
+    );
+    let elapsed = start.elapsed();
+
+    // Every phase is reported separately, in the order in which the phases are applied.
+    let size_bound = *args.get_one::<usize>("size-bound").unwrap();
+    let limit = *args.get_one::<usize>("limit").unwrap();
+    let target_name = state_to_string(&target);
+    let mut text = String::new();
+    let mut phases = Vec::new();
+    for (i, phase) in control.phases().iter().enumerate() {
+        let mut report = ControlReport::new(model_path, &target_name, phase.mode());
+        report.add_control_perturbations(&phase.top_k_by_robustness(limit, size_bound));
+        text.push_str(&format!("Phase {}:\n{}", i, report));
+        phases.push(report.to_json());
+    }
+    text.push_str(&format!("Elapsed (control map): {}ms", elapsed.as_millis()));
+    let value = json!({
+        "model": model_path,
+        "target": target_name,
+        "phases": phases,
+        "timings_ms": { "control map": elapsed.as_millis() as u64 },
+    });
+    write_output(args, text, &value)
+}
+
+/// Read the network of `--model` together with the `--source` and `--target` states, which
+/// default to the `#source:`/`#target:` lines of the model.
+fn read_source_target(
+    args: &ArgMatches,
+) -> Result<(ArrayBitVector, ArrayBitVector, BooleanNetwork), ControlError> {
+    let model_string = read_file(args.get_one::<String>("model").unwrap())?;
+    match (
+        args.get_one::<String>("source"),
+        args.get_one::<String>("target"),
+    ) {
+        (Some(source), Some(target)) => Ok((
+            try_string_to_state(source)?,
+            try_string_to_state(target)?,
+            BooleanNetwork::try_from(model_string.as_str())
+                .map_err(ControlError::InvalidNetwork)?,
+        )),
+        (source, target) => {
+            let (header_source, header_target, network) =
+                try_parse_experiment(model_string.as_str())?;
+            let source = source.map(|it| try_string_to_state(it)).transpose()?;
+            let target = target.map(|it| try_string_to_state(it)).transpose()?;
+            Ok((
+                source.unwrap_or(header_source),
+                target.unwrap_or(header_target),
+                network,
+            ))
+        }
+    }
+}
+
+fn run_phenotype_control(args: &ArgMatches) -> Result<(), ControlError> {
+    let model_path = args.get_one::<String>("model").unwrap();
+    let phenotype_formula = args.get_one::<String>("phenotype").unwrap();
+    let (graph, phenotype) = read_phenotype_problem(args)?;
+    let attractor_search = args
+        .get_one::<String>("attractors")
+        .unwrap()
+        .parse::<AttractorSearch>()?;
+    let size_bound = *args.get_one::<usize>("size-bound").unwrap();
+    let perturbable = graph
+        .variables()
+        .filter(|it| graph.get_perturbation_parameter(*it).is_some())
+        .collect::<Vec<_>>();
+
+    let limit = *args.get_one::<usize>("limit").unwrap();
+
+    let start = Instant::now();
+    let mode = args.get_one::<String>("mode").unwrap();
+    if mode == "one-step" {
+        // One-step control results in a source-target control map.
+        let admissible_perturbations =
+            graph.try_mk_admissible_perturbations(&perturbable, size_bound)?;
+        let (sources, quantifier) = match args.get_one::<String>("source") {
+            Some(source) => (
+                graph.vertex(&try_string_to_state(source)?),
+                SourceQuantifier::Exists,
+            ),
+            None => (graph.mk_unit_colored_vertices(), SourceQuantifier::ForAll),
+        };
+        let control = graph.phenotype_one_step_control_from_set(
+            &sources,
+            quantifier,
+            phenotype,
+            admissible_perturbations,
+            &attractor_search,
+        );
+        let elapsed = start.elapsed();
+        if let Some(path) = args.get_one::<String>("save-map") {
+            control.save(path)?;
+        }
+
+        let mut report = ControlReport::new(model_path, phenotype_formula, ControlMode::OneStep);
+        report.add_control_perturbations(&control.top_k_by_robustness(limit, size_bound));
+        report.add_timing("control map", elapsed);
+        return write_report(args, &report);
+    }
+
+    let (mode, control) = if mode == "temporary" {
+        let control = graph.try_ceiled_phenotype_temporary_control(
+            phenotype,
+            size_bound,
+            perturbable,
+            attractor_search,
+        )?;
+        (ControlMode::Temporary, control)
+    } else {
+        let control = graph.try_ceiled_phenotype_permanent_control(
+            phenotype,
+            size_bound,
+            perturbable,
+            attractor_search,
+        )?;
+        (ControlMode::Permanent, control)
+    };
+    let elapsed = start.elapsed();
+    if let Some(path) = args.get_one::<String>("save-map") {
+        control.save(path)?;
+    }
+
+    let mut report = ControlReport::new(model_path, phenotype_formula, mode);
+    let mut perturbations = control.cheapest_perturbations(&PerturbationCosts::new(), size_bound);
+    perturbations.truncate(limit);
+    report.add_bounded_perturbations(&perturbations);
+    report.add_timing("control map", elapsed);
+    write_report(args, &report)
+}
+
+fn run_bounded_phenotype_control(args: &ArgMatches) -> Result<(), ControlError> {
+    let model_path = args.get_one::<String>("model").unwrap();
+    let phenotype_formula = args.get_one::<String>("phenotype").unwrap();
+    let (graph, phenotype) = read_phenotype_problem(args)?;
+    let size_bound = *args.get_one::<usize>("size-bound").unwrap();
+
+    let results = match args.get_one::<String>("attractors") {
+        Some(attractors) => {
+            let attractor_search = attractors.parse::<AttractorSearch>()?;
+            bounded_phenotype_control_with(&graph, &phenotype, size_bound, &attractor_search)
+        }
+        None => bounded_phenotype_control(&graph, &phenotype, size_bound),
+    };
+    let report = ControlReport::from_bounded_results(model_path, phenotype_formula, &results);
+    write_report(args, &report)
+}
+
+fn run_enumerate(args: &ArgMatches) -> Result<(), ControlError> {
+    let map_path = args.get_one::<String>("map").unwrap();
+    let size_bound = *args.get_one::<usize>("size-bound").unwrap();
+    let limit = *args.get_one::<usize>("limit").unwrap();
+
+    let kind = serde_json::from_str::<Value>(read_file(map_path)?.as_str())
+        .map_err(|e| ControlError::InvalidControlMap(e.to_string()))?["kind"]
+        .clone();
+    let report = match kind.as_str() {
+        Some("phenotype-control-map") => {
+            let control = PhenotypeControlMap::load(map_path)?;
+            let mut report = ControlReport::new(map_path, "phenotype", control.mode());
+            let mut perturbations =
+                control.cheapest_perturbations(&PerturbationCosts::new(), size_bound);
+            perturbations.truncate(limit);
+            report.add_bounded_perturbations(&perturbations);
+            report
+        }
+        Some("control-map") => {
+            let control = ControlMap::load(map_path)?;
+            let mut report = ControlReport::new(map_path, "target", control.mode());
+            report.add_control_perturbations(&control.top_k_by_robustness(limit, size_bound));
+            report
+        }
+        _ => {
+            return Err(ControlError::InvalidControlMap(format!(
+                "Unknown map kind {}.",
+                kind
+            )))
+        }
+    };
+    write_report(args, &report)
+}
+
+/// Read the network of `--model` and the `--phenotype` states, resolving both through
+/// the benchmark `--config` when it is given.
+fn read_phenotype_problem(
+    args: &ArgMatches,
+) -> Result<(PerturbationGraph, GraphVertices), ControlError> {
+    let model = args.get_one::<String>("model").unwrap();
+    let phenotype = args.get_one::<String>("phenotype").unwrap();
+    match args.get_one::<String>("config") {
+        Some(config) => {
+            let config = ExperimentConfig::try_load(config)?;
+            let model_config = config.try_get_model(model)?;
+            let network = model_config.try_load_network()?;
+            let controllable = model_config
+                .try_controllable_variables(&network)?
+                .into_iter()
+                .map(|it| network.get_variable_name(it).clone())
+                .collect::<Vec<_>>();
+            let graph = mk_perturbation_graph(&network, args, Some(controllable))?;
+            let phenotype = model_config.try_phenotype(phenotype, graph.as_perturbed())?;
+            Ok((graph, phenotype))
+        }
+        None => {
+            let network = read_model(model)?;
+            let graph = mk_perturbation_graph(&network, args, None)?;
+            let phenotype =
+                PhenotypeExpression::try_parse(phenotype)?.try_build(graph.as_perturbed())?;
+            Ok((graph, phenotype))
+        }
+    }
+}
+
+/// Create a perturbation graph where the `--perturbable` variables (or the `default`
+/// variables, or all variables), except for the `--uncontrollable` ones, can be perturbed.
+fn mk_perturbation_graph(
+    network: &BooleanNetwork,
+    args: &ArgMatches,
+    default: Option<Vec<String>>,
+) -> Result<PerturbationGraph, ControlError> {
+    let uncontrollable = args
+        .get_many::<String>("uncontrollable")
+        .map(|it| it.map(|name| name.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    for name in &uncontrollable {
+        if network.as_graph().find_variable(name).is_none() {
+            return Err(ControlError::UnknownVariable(name.to_string()));
+        }
+    }
+    let perturbable = match args.get_many::<String>("perturbable") {
+        Some(names) => names.map(|it| it.to_string()).collect::<Vec<_>>(),
+        None => default.unwrap_or_else(|| {
+            network
+                .variables()
+                .map(|it| network.get_variable_name(it).clone())
+                .collect()
+        }),
+    };
+    let perturbable = perturbable
+        .iter()
+        .map(|it| it.as_str())
+        .filter(|it| !uncontrollable.contains(it))
+        .collect::<Vec<_>>();
+    PerturbationGraph::try_with_perturbable_names(network, &perturbable)
+}
+
+fn read_file(path: &str) -> Result<String, ControlError> {
+    std::fs::read_to_string(path).map_err(|e| ControlError::Io(format!("{}: {}", path, e)))
+}
+
+fn read_model(path: &str) -> Result<BooleanNetwork, ControlError> {
+    BooleanNetwork::try_from(read_file(path)?.as_str()).map_err(ControlError::InvalidNetwork)
+}
+
+fn state_to_string(state: &ArrayBitVector) -> String {
+    state
+        .values()
+        .into_iter()
+        .map(|it| if it { '1' } else { '0' })
+        .collect()
+}
+
+fn write_report(args: &ArgMatches, report: &ControlReport) -> Result<(), ControlError> {
+    write_output(args, report.to_string(), &report.to_json())
+}
+
+/// Print the `text` or `json` result (based on `--format`) into `--output` or standard output.
+fn write_output(
+    args: &ArgMatches,
+    text: String,
+    json: &serde_json::Value,
+) -> Result<(), ControlError> {
+    let data = if args.get_one::<String>("format").unwrap() == "json" {
+        serde_json::to_string_pretty(json).map_err(|e| ControlError::Io(e.to_string()))?
+    } else {
+        text
+    };
+    match args.get_one::<String>("output") {
+        Some(path) => std::fs::write(path, data).map_err(|e| ControlError::Io(e.to_string())),
+        None => {
+            println!("{}", data);
+            Ok(())
+        }
+    }
+}
@@ -1,8 +1,13 @@
-use crate::control::ControlMap;
-use biodivine_lib_bdd::Bdd;
+use crate::aeon::reachability::{backward, forward_closed};
+use crate::control::{ControlMap, ControlMode, ControlPerturbation};
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::_symbolic_utils::mk_bdd_up_to_bound;
+use biodivine_lib_bdd::{Bdd, BddPartialValuation, BddVariable};
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::projected_iteration::RawProjection;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
 use biodivine_lib_param_bn::VariableId;
+use std::collections::HashMap;
 
 impl ControlMap {
     /// Remove from this control map any results that *do not* perturb `variable`.
@@ -19,6 +24,52 @@ impl ControlMap {
         self.perturbation_set = self.perturbation_set.minus(&exclude);
     }
 
+    /// Remove from this control map any results that do not perturb the regulation from
+    /// `regulator` to `target`.
+    pub fn require_edge_perturbation(&mut self, regulator: VariableId, target: VariableId) {
+        let require = self.context.fix_edge_perturbation(regulator, target);
+        self.perturbation_set = self.perturbation_set.intersect(&require);
+    }
+
+    /// Remove from this control map any results that perturb the regulation from
+    /// `regulator` to `target`.
+    pub fn exclude_edge_perturbation(&mut self, regulator: VariableId, target: VariableId) {
+        let exclude = self.context.fix_edge_perturbation(regulator, target);
+        self.perturbation_set = self.perturbation_set.minus(&exclude);
+    }
+
+    /// The perturbation graph for which this map was computed.
+    pub fn as_perturbation_graph(&self) -> &PerturbationGraph {
+        &self.context
+    }
+
+    /// The kind of control under which this map was computed.
+    pub fn mode(&self) -> ControlMode {
+        self.mode
+    }
+
+    /// The colors against which the robustness of perturbations is measured.
+    ///
+    /// For source-target control, these are the `compute_params` where the target is
+    /// an attractor of the original network (i.e. where its original strong basin is not
+    /// empty), so they do not depend on whether the caller already restricted the
+    /// `compute_params` (e.g. using `experiment_utils::get_all_params_with_attractor`). For
+    /// phenotype one-step control, these are the admissible perturbations.
+    ///
+    /// Permanent control does not need the original strong basin of the target otherwise, so
+    /// it is only computed on the first call of this method (or of a method which measures
+    /// robustness).
+    pub fn colors(&self) -> &GraphColors {
+        self.colors.get_or_init(|| {
+            let target = self
+                .robustness_target
+                .as_ref()
+                .expect("The map has neither colors nor a target.");
+            let weak_basin = backward(self.context.as_original(), target);
+            forward_closed(self.context.as_original(), &weak_basin).colors()
+        })
+    }
+
     pub fn as_bdd(&self) -> &Bdd {
         self.perturbation_set.as_bdd()
     }
@@ -28,30 +79,26 @@ impl ControlMap {
     }
 
     pub fn controllable_colors(&self) -> Bdd {
+        self.model_colors(self.perturbation_set.colors().into_bdd())
+    }
+
+    /// Remove perturbation parameters from the given color set. Then fix the state variables
+    /// and perturbation parameters to true so that the cardinality of the result is the number
+    /// of original colors.
+    fn model_colors(&self, colors: Bdd) -> Bdd {
         let bdd_context = self.context.as_symbolic_context();
-        let mut bdd = self.perturbation_set.colors().into_bdd();
-        for v in self.context.variables() {
-            let parameter = self.context.get_perturbation_parameter(v);
-            if let Some(parameter) = parameter {
-                for (_, bdd_var) in bdd_context.get_explicit_function_table(parameter) {
-                    // There should be only one because arity is zero
-                    bdd = bdd.var_exists(bdd_var);
-                }
-            }
+        let parameter_vars = self.all_perturbation_vars();
+        let mut bdd = colors;
+        for p_var in &parameter_vars {
+            bdd = bdd.var_exists(*p_var);
         }
 
         // Now just fix the control parameters and variables to true so that the final cardinality is correct.
         for s_var in bdd_context.state_variables() {
             bdd = bdd.var_select(*s_var, true);
         }
-        for v in self.context.variables() {
-            let parameter = self.context.get_perturbation_parameter(v);
-            if let Some(parameter) = parameter {
-                for (_, bdd_var) in bdd_context.get_explicit_function_table(parameter) {
-                    // There should be only one because arity is zero
-                    bdd = bdd.var_select(bdd_var, true);
-                }
-            }
+        for p_var in &parameter_vars {
+            bdd = bdd.var_select(*p_var, true);
         }
 
         bdd
@@ -67,4 +114,425 @@ impl ControlMap {
     pub fn jump_vertices(&self) -> f64 {
         self.perturbation_set.vertices().approx_cardinality()
     }
+
+    /// Compute the robustness of the given `perturbation`, i.e. the fraction of the map
+    /// `colors` for which it works.
+    ///
+    /// Perturbations of unknown or non-perturbable variables have zero robustness. The
+    /// perturbation does not perturb any regulation.
+    pub fn robustness(&self, perturbation: &HashMap<String, bool>) -> f64 {
+        let mut restricted = self.clone();
+        let mut matched = 0;
+        for v in self.context.variables() {
+            let name = self.context.as_original().get_variable_name(v);
+            if let Some(value) = perturbation.get(&name) {
+                restricted.require_perturbation(v, Some(*value));
+                matched += 1;
+            } else {
+                restricted.exclude_perturbation(v, None);
+            }
+        }
+        if matched < perturbation.len() {
+            return 0.0;
+        }
+        for (regulator, target) in self.context.edge_perturbations() {
+            restricted.exclude_edge_perturbation(regulator, target);
+        }
+        self.relative_cardinality(&restricted.controllable_colors())
+    }
+
+    /// Return (up to) `k` perturbations of size at most `max_size` with the highest robustness
+    /// (see `ControlMap::robustness`), in decreasing order. Perturbations with the same
+    /// robustness are ordered by size. Both perturbed variables and perturbed regulations
+    /// count towards the size.
+    ///
+    /// Every perturbation of size at most `max_size` is enumerated (and sorted), so the cost
+    /// grows with the number of such perturbations in the map, not with `k`. On large maps
+    /// (e.g. the MAPK models), keep `max_size` small.
+    pub fn top_k_by_robustness(
+        &self,
+        k: usize,
+        max_size: usize,
+    ) -> Vec<(ControlPerturbation, f64)> {
+        let bdd_vars = self.context.as_symbolic_context().bdd_variable_set();
+        let up_to_size = mk_bdd_up_to_bound(bdd_vars, &self.all_perturbation_vars(), max_size);
+
+        let mut result = self
+            .perturbations_in(self.as_bdd().and(&up_to_size))
+            .map(|item| {
+                let working_colors = self.model_colors(item.working_colors.as_bdd().clone());
+                let robustness = self.relative_cardinality(&working_colors);
+                (item, robustness)
+            })
+            .collect::<Vec<_>>();
+        result
+            .sort_by(|(a, a_rob), (b, b_rob)| b_rob.total_cmp(a_rob).then(a.size().cmp(&b.size())));
+        result.truncate(k);
+        result
+    }
+
+    /// The cardinality of the `model_colors` relative to the number of original colors
+    /// in the map `colors`.
+    fn relative_cardinality(&self, model_colors: &Bdd) -> f64 {
+        let all = self
+            .model_colors(self.colors().as_bdd().clone())
+            .cardinality();
+        if all == 0.0 {
+            0.0
+        } else {
+            model_colors.cardinality() / all
+        }
+    }
+
+    /// Iterate over all distinct perturbations contained in this control map, together with
+    /// the colors for which they work.
+    pub fn perturbations(&self) -> impl Iterator<Item = ControlPerturbation> + '_ {
+        self.perturbations_in(self.as_bdd().clone())
+    }
+
+    /// Compute the inclusion-minimal perturbations of this control map, ordered by size.
+    ///
+    /// A perturbation is minimal for a color if no strict subset of its perturbed variables
+    /// (with the same values) and regulations controls the network for the same color. The
+    /// working colors of each result only contain the colors for which the perturbation is
+    /// minimal.
+    pub fn minimal_perturbations(&self) -> Vec<ControlPerturbation> {
+        /*
+           First, we forget the values of unperturbed variables, such that every perturbation
+           is represented by its perturbation parameters, the values of perturbed variables,
+           and colors. Then we compute all perturbations which are a strict superset of
+           some working perturbation by repeatedly "adding" one perturbed variable (with
+           an arbitrary value). The minimal perturbations are the rest.
+        */
+        let symbolic_context = self.context.as_symbolic_context();
+        let perturbation_vars = self.perturbation_vars();
+        let mut working = self.as_bdd().clone();
+        for var in self.context.variables() {
+            let state_var = symbolic_context.get_state_variable(var);
+            if let Some((_, p_var)) = perturbation_vars.iter().find(|(v, _)| *v == var) {
+                let is_perturbed = working.var_select(*p_var, true);
+                let is_not_perturbed = working.var_select(*p_var, false).var_exists(state_var);
+                working = is_perturbed.or(&is_not_perturbed);
+            } else {
+                working = working.var_exists(state_var);
+            }
+        }
+
+        let mut non_minimal = symbolic_context.bdd_variable_set().mk_false();
+        loop {
+            let smaller = working.or(&non_minimal);
+            let mut larger = non_minimal.clone();
+            for p_var in &self.all_perturbation_vars() {
+                let p_literal = symbolic_context.bdd_variable_set().mk_literal(*p_var, true);
+                let extended = smaller.var_select(*p_var, false).var_exists(*p_var);
+                larger = larger.or(&extended.and(&p_literal));
+            }
+            if larger == non_minimal {
+                break;
+            }
+            non_minimal = larger;
+        }
+
+        let mut result = self
+            .perturbations_in(working.and_not(&non_minimal))
+            .collect::<Vec<_>>();
+        result.sort_by_key(|it| it.size());
+        result
+    }
+
+    /// Symbolic variables of the perturbation parameters of perturbable network variables.
+    fn perturbation_vars(&self) -> Vec<(VariableId, BddVariable)> {
+        self.context
+            .variables()
+            .filter_map(|var| {
+                self.context.get_perturbation_parameter(var).map(|param| {
+                    let table = self
+                        .context
+                        .as_symbolic_context()
+                        .get_explicit_function_table(param);
+                    (var, table.symbolic_variables()[0])
+                })
+            })
+            .collect()
+    }
+
+    /// Symbolic variables of all perturbation parameters, including perturbable regulations.
+    fn all_perturbation_vars(&self) -> Vec<BddVariable> {
+        let mut result = self
+            .perturbation_vars()
+            .into_iter()
+            .map(|(_, p_var)| p_var)
+            .chain(
+                self.context
+                    .edge_perturbation_vars()
+                    .into_iter()
+                    .map(|(_, p_var)| p_var),
+            )
+            .collect::<Vec<_>>();
+        result.sort();
+        result
+    }
+
+    /// Enumerate the perturbations in the given control map `bdd`.
+    fn perturbations_in(&self, bdd: Bdd) -> impl Iterator<Item = ControlPerturbation> + '_ {
+        let perturbation_vars = self.perturbation_vars();
+        let retained = self.all_perturbation_vars();
+
+        // First, we enumerate the sets of perturbed variables and regulations, then the
+        // values of each set.
+        RawProjection::new(retained, &bdd)
+            .into_iter()
+            .flat_map(move |is_perturbed| {
+                self.perturbation_values(&bdd, &perturbation_vars, is_perturbed)
+            })
+    }
+
+    /// Enumerate the values of perturbed variables (and their working colors) for one
+    /// valuation of perturbation parameters.
+    fn perturbation_values(
+        &self,
+        bdd: &Bdd,
+        perturbation_vars: &[(VariableId, BddVariable)],
+        is_perturbed: BddPartialValuation,
+    ) -> impl Iterator<Item = ControlPerturbation> + '_ {
+        let symbolic_context = self.context.as_symbolic_context();
+        let perturbed_variables = perturbation_vars
+            .iter()
+            .filter(|(_, p_var)| is_perturbed.get_value(*p_var).unwrap())
+            .map(|(var, _)| *var)
+            .collect::<Vec<_>>();
+        let network = self.context.as_network();
+        let perturbed_edges = self
+            .context
+            .edge_perturbation_vars()
+            .into_iter()
+            .filter(|(_, p_var)| is_perturbed.get_value(*p_var).unwrap())
+            .map(|((regulator, target), _)| {
+                (
+                    network.get_variable_name(regulator).clone(),
+                    network.get_variable_name(target).clone(),
+                    self.context
+                        .get_edge_perturbation_value(regulator, target)
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Remove the perturbation parameters and the values of unperturbed variables, since
+        // these only describe the jump source.
+        let mut control_subset = bdd.restrict(&is_perturbed.to_values());
+        for var in self.context.variables() {
+            if !perturbed_variables.contains(&var) {
+                control_subset =
+                    control_subset.var_exists(symbolic_context.get_state_variable(var));
+            }
+        }
+
+        let perturbed_state_vars = perturbed_variables
+            .iter()
+            .map(|var| symbolic_context.get_state_variable(*var))
+            .collect::<Vec<_>>();
+        let is_perturbed_clause = symbolic_context
+            .bdd_variable_set()
+            .mk_conjunctive_clause(&is_perturbed);
+        RawProjection::new(perturbed_state_vars, &control_subset)
+            .into_iter()
+            .map(move |state_vector| {
+                let mut perturbation = HashMap::new();
+                for var in &perturbed_variables {
+                    let state_var = symbolic_context.get_state_variable(*var);
+                    perturbation.insert(
+                        self.context.as_original().get_variable_name(*var),
+                        state_vector.get_value(state_var).unwrap(),
+                    );
+                }
+                // The restricted BDD no longer depends on perturbation parameters, so we fix
+                // them back to their values to obtain a valid color set.
+                let working_colors = control_subset
+                    .restrict(&state_vector.to_values())
+                    .and(&is_perturbed_clause);
+                ControlPerturbation {
+                    perturbation,
+                    edges: perturbed_edges.clone(),
+                    working_colors: self.context.empty_colors().copy(working_colors),
+                }
+            })
+    }
+}
+
+impl ControlPerturbation {
+    /// The number of perturbed variables and regulations.
+    pub fn size(&self) -> usize {
+        self.perturbation.len() + self.edges.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::ControlMap;
+    use crate::error::ControlError;
+    use crate::experiment_utils::get_all_params_with_attractor;
+    use crate::perturbation::PerturbationGraph;
+    use crate::tests::myeloid_fixtures::{attractor_states, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn test_enumerate_perturbations_myeloid() {
+        let model = myeloid_witness();
+        let perturbations = PerturbationGraph::new(&model);
+        let states = attractor_states(&perturbations);
+        let (source, target) = (&states[0], &states[1]);
+
+        let control = perturbations.one_step_control(source, target, perturbations.unit_colors());
+        let enumerated = control.perturbations().collect::<Vec<_>>();
+
+        // Every perturbation works for the single color of the witness model.
+        assert!(!enumerated.is_empty());
+        for item in &enumerated {
+            assert_eq!(1.0, item.working_colors.approx_cardinality());
+        }
+
+        // Perturbations of all variables correspond to the jump states in the strong basin.
+        let mut all_perturbed = control.clone();
+        for v in perturbations.variables() {
+            all_perturbed.require_perturbation(v, None);
+        }
+        let full_size = enumerated
+            .iter()
+            .filter(|it| it.perturbation.len() == model.num_vars())
+            .count();
+        assert_eq!(all_perturbed.as_bdd().cardinality(), full_size as f64);
+
+        // The trivial perturbation is included, the empty perturbation is not.
+        assert!(enumerated.iter().any(|it| {
+            perturbations.variables().all(|v| {
+                let name = model.get_variable_name(v);
+                it.perturbation.get(name) == Some(&target.get(v.to_index()))
+            })
+        }));
+        assert!(enumerated.iter().all(|it| !it.perturbation.is_empty()));
+    }
+
+    #[test]
+    pub fn test_minimal_perturbations_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let states = attractor_states(&perturbations);
+
+        let control =
+            perturbations.one_step_control(&states[0], &states[1], perturbations.unit_colors());
+        let all = control.perturbations().collect::<Vec<_>>();
+        let minimal = control.minimal_perturbations();
+        assert!(!minimal.is_empty());
+        assert!(minimal.len() < all.len());
+
+        let is_sub_perturbation = |a: &HashMap<String, bool>, b: &HashMap<String, bool>| {
+            a.iter().all(|(k, v)| b.get(k) == Some(v))
+        };
+
+        // Results are ordered by size.
+        for pair in minimal.windows(2) {
+            assert!(pair[0].perturbation.len() <= pair[1].perturbation.len());
+        }
+        // The witness model has a single color, so minimality is just set inclusion.
+        for item in &all {
+            let is_minimal = !all.iter().any(|other| {
+                other.perturbation.len() < item.perturbation.len()
+                    && is_sub_perturbation(&other.perturbation, &item.perturbation)
+            });
+            let found = minimal
+                .iter()
+                .any(|it| it.perturbation == item.perturbation);
+            assert_eq!(is_minimal, found);
+        }
+    }
+
+    #[test]
+    pub fn test_robustness_myeloid() {
+        // Attractors are taken from the witness model, control is computed in the
+        // parametrised model.
+        let states = attractor_states(&PerturbationGraph::new(&myeloid_witness()));
+        let (source, target) = (&states[0], &states[1]);
+
+        let model_string = &std::fs::read_to_string("models/myeloid_4unknown.aeon").unwrap();
+        let model = BooleanNetwork::try_from(model_string.as_str()).unwrap();
+        let perturbations = PerturbationGraph::new(&model);
+        let attractor_colors = get_all_params_with_attractor(&perturbations, target);
+        let control = perturbations.one_step_control(source, target, &attractor_colors);
+
+        // Robustness is relative to the colors where the target is an attractor, even if the
+        // map is computed for all colors.
+        assert_eq!(&attractor_colors, control.colors());
+        let unit_control =
+            perturbations.one_step_control(source, target, perturbations.unit_colors());
+        assert_eq!(&attractor_colors, unit_control.colors());
+
+        let top = control.top_k_by_robustness(5, 3);
+        assert!(!top.is_empty() && top.len() <= 5);
+        for (item, robustness) in &top {
+            assert!(item.perturbation.len() <= 3);
+            assert!(*robustness > 0.0 && *robustness <= 1.0);
+            assert_eq!(*robustness, control.robustness(&item.perturbation));
+            assert_eq!(*robustness, unit_control.robustness(&item.perturbation));
+        }
+        // The smallest perturbation with the best robustness comes first.
+        assert_eq!(2, top[0].0.perturbation.len());
+        for pair in top.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+
+        // Perturbations of unknown variables never work.
+        let unknown = HashMap::from([(String::from("NOT_A_VARIABLE"), true)]);
+        assert_eq!(0.0, control.robustness(&unknown));
+    }
+
+    #[test]
+    pub fn test_edge_perturbations() {
+        // `B` copies `A`, so perturbing `A` alone cannot reach a state where `A` and `B` differ.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B\n$A: A\n$B: A").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        let perturbations =
+            PerturbationGraph::try_with_edge_perturbations(&model, &[(a, None)], &[(a, b, false)])
+                .unwrap();
+        assert_eq!(vec![(a, b)], perturbations.edge_perturbations());
+        assert_eq!(Some(false), perturbations.get_edge_perturbation_value(a, b));
+        assert!(matches!(
+            PerturbationGraph::try_with_edge_perturbations(&model, &[], &[(b, a, true)]),
+            Err(ControlError::UnknownRegulation(..))
+        ));
+
+        let source = ArrayBitVector::from(vec![true, true]);
+        let target = ArrayBitVector::from(vec![true, false]);
+        let control =
+            perturbations.permanent_control(&source, &target, perturbations.unit_colors());
+        let minimal = control.minimal_perturbations();
+        assert_eq!(1, minimal.len());
+        assert!(minimal[0].perturbation.is_empty());
+        assert_eq!(
+            vec![(String::from("A"), String::from("B"), false)],
+            minimal[0].edges
+        );
+        assert!(control.perturbations().all(|it| it.edges.len() == 1));
+        assert_eq!(1.0, control.controllable_colors_cardinality());
+
+        let mut without_edge = control.clone();
+        without_edge.exclude_edge_perturbation(a, b);
+        assert!(without_edge.perturbations().next().is_none());
+
+        // The perturbable regulations are preserved when the map is saved.
+        let path = std::env::temp_dir().join("pbn_control_test_edge_perturbations.json");
+        control.save(&path).unwrap();
+        let loaded = ControlMap::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(control.as_bdd(), loaded.as_bdd());
+        assert_eq!(
+            Some(false),
+            loaded
+                .as_perturbation_graph()
+                .get_edge_perturbation_value(a, b)
+        );
+    }
 }
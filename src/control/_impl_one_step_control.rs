@@ -1,7 +1,8 @@
-use crate::control::ControlMap;
-use crate::perturbation::PerturbationGraph;
+use crate::control::{ControlMap, ControlMode};
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
 use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColors;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
+use std::sync::OnceLock;
 
 impl PerturbationGraph {
     /// Compute one-step control map. That is, controls which work by applying the perturbation
@@ -11,6 +12,36 @@ impl PerturbationGraph {
         source: &ArrayBitVector,
         target: &ArrayBitVector,
         compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.one_step_control_to_set(source, &self.vertex(target), compute_params)
+    }
+
+    /// The same as `one_step_control`, but the `target` is a set of states, for example a whole
+    /// (complex) attractor or a subspace (see `PerturbationGraph::mk_subspace`).
+    pub fn one_step_control_to_set(
+        &self,
+        source: &ArrayBitVector,
+        target: &GraphColoredVertices,
+        compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.one_step_control_from_set(
+            &self.vertex(source),
+            target,
+            // For a single source state, both quantifiers are equivalent.
+            SourceQuantifier::Exists,
+            compute_params,
+        )
+    }
+
+    /// The same as `one_step_control_to_set`, but the perturbation is applied in a set of
+    /// `sources` states. The `quantifier` decides whether the perturbation must work from
+    /// every source state, or from at least one.
+    pub fn one_step_control_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        target: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+        compute_params: &GraphColors,
     ) -> ControlMap {
         /*
            To eventually stabilize in target, we have to reach its strong basin using a
@@ -20,22 +51,27 @@ impl PerturbationGraph {
            Note that colors where target is not in an attractor will be eliminated using the
            strong basin procedure.
         */
-        let target_set = self.vertex(target).intersect_colors(compute_params);
+        let target_set = target.intersect_colors(compute_params);
         let weak_basin = crate::aeon::reachability::backward(self.as_original(), &target_set);
         let strong_basin =
             crate::aeon::reachability::forward_closed(self.as_original(), &weak_basin);
-        let can_jump_to = self.post_perturbation(source, &strong_basin);
+        let can_jump_to = self.post_perturbation_from_set(sources, &strong_basin, quantifier);
         ControlMap {
             perturbation_set: can_jump_to,
             context: self.clone(),
+            mode: ControlMode::OneStep,
+            colors: OnceLock::from(strong_basin.colors()),
+            robustness_target: None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::perturbation::PerturbationGraph;
+    use crate::perturbation::{PerturbationGraph, SourceQuantifier};
+    use crate::tests::myeloid_fixtures::{attractor_states, myeloid_witness};
     use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
     use biodivine_lib_param_bn::BooleanNetwork;
     use std::convert::TryFrom;
 
@@ -121,6 +157,53 @@ mod tests {
         test_trivial_one_step_control("erbb");
     }
 
+    #[test]
+    pub fn test_one_step_from_source_set_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let states = attractor_states(&perturbations);
+        let (source_a, source_b, target) = (&states[0], &states[1], &states[2]);
+        let sources = perturbations
+            .vertex(source_a)
+            .union(&perturbations.vertex(source_b));
+        let target_set = perturbations.vertex(target);
+        let colors = perturbations.unit_colors();
+
+        let control_a = perturbations.one_step_control(source_a, target, colors);
+        let control_b = perturbations.one_step_control(source_b, target, colors);
+        let exists = perturbations.one_step_control_from_set(
+            &sources,
+            &target_set,
+            SourceQuantifier::Exists,
+            colors,
+        );
+        let for_all = perturbations.one_step_control_from_set(
+            &sources,
+            &target_set,
+            SourceQuantifier::ForAll,
+            colors,
+        );
+
+        // Existential jumps are jumps from either of the sources.
+        assert_eq!(
+            exists.as_colored_vertices(),
+            &control_a
+                .as_colored_vertices()
+                .union(control_b.as_colored_vertices())
+        );
+        // Universal jumps are a strict subset, since some perturbations only work for one source.
+        assert!(for_all
+            .as_colored_vertices()
+            .is_subset(exists.as_colored_vertices()));
+        assert!(for_all.as_bdd().cardinality() < exists.as_bdd().cardinality());
+
+        // Jumping directly into target works from both sources.
+        let mut trivial_control = for_all.clone();
+        for v in perturbations.variables() {
+            trivial_control.require_perturbation(v, Some(target.get(usize::from(v))));
+        }
+        assert_eq!(1.0, trivial_control.as_bdd().cardinality());
+    }
+
     /*
     #[test]
     pub fn test_trival_one_step_all() {
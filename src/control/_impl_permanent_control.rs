@@ -1,7 +1,8 @@
-use crate::control::ControlMap;
-use crate::perturbation::PerturbationGraph;
+use crate::control::{ControlMap, ControlMode};
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
 use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColors;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
+use std::sync::OnceLock;
 
 impl PerturbationGraph {
     /// Compute permanent control map. That is, controls which work when a perturbation is
@@ -11,18 +12,53 @@ impl PerturbationGraph {
         source: &ArrayBitVector,
         target: &ArrayBitVector,
         compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.permanent_control_to_set(source, &self.vertex(target), compute_params)
+    }
+
+    /// The same as `permanent_control`, but the `target` is a set of states, for example a whole
+    /// (complex) attractor or a subspace (see `PerturbationGraph::mk_subspace`).
+    pub fn permanent_control_to_set(
+        &self,
+        source: &ArrayBitVector,
+        target: &GraphColoredVertices,
+        compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.permanent_control_from_set(
+            &self.vertex(source),
+            target,
+            // For a single source state, both quantifiers are equivalent.
+            SourceQuantifier::Exists,
+            compute_params,
+        )
+    }
+
+    /// The same as `permanent_control_to_set`, but the perturbation is applied in a set of
+    /// `sources` states. The `quantifier` decides whether the perturbation must work from
+    /// every source state, or from at least one.
+    pub fn permanent_control_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        target: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+        compute_params: &GraphColors,
     ) -> ControlMap {
         /*
            Permanent control works exactly as one-step, but in the perturbed graph instead of original.
         */
-        let target_set = self.vertex(target).intersect_colors(compute_params);
+        let target_set = target.intersect_colors(compute_params);
         let weak_basin = crate::aeon::reachability::backward(self.as_perturbed(), &target_set);
         let strong_basin =
             crate::aeon::reachability::forward_closed(self.as_perturbed(), &weak_basin);
-        let can_jump_to = self.post_perturbation(source, &strong_basin);
+        let can_jump_to = self.post_perturbation_from_set(sources, &strong_basin, quantifier);
         ControlMap {
             perturbation_set: can_jump_to,
             context: self.clone(),
+            mode: ControlMode::Permanent,
+            // The original strong basin is only needed for robustness, so it is computed
+            // when the colors are first used (see `ControlMap::colors`).
+            colors: OnceLock::new(),
+            robustness_target: Some(target_set),
         }
     }
 }
@@ -31,6 +67,7 @@ impl PerturbationGraph {
 mod tests {
     use crate::perturbation::PerturbationGraph;
     use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
     use biodivine_lib_param_bn::BooleanNetwork;
     use std::convert::TryFrom;
 
@@ -125,6 +162,50 @@ mod tests {
         test_trivial_permanent_control("erbb");
     }
 
+    #[test]
+    pub fn test_permanent_control_to_complex_attractor() {
+        // `x` and `y` oscillate, `z` is bistable, so there are two cyclic attractors.
+        let model =
+            BooleanNetwork::try_from("x -> y \n y -| x \n z -> z \n $x: !y \n $y: x \n $z: z")
+                .unwrap();
+        let perturbations = PerturbationGraph::new(&model);
+        let z = model.as_graph().find_variable("z").unwrap();
+
+        let attractors = crate::aeon::attractors::compute(perturbations.as_original());
+        assert_eq!(2, attractors.len());
+        let target = attractors
+            .iter()
+            .find(|it| it.is_subset(&perturbations.fix_variable(z, true)))
+            .unwrap();
+        let source = ArrayBitVector::from(vec![false, false, false]);
+
+        let control =
+            perturbations.permanent_control_to_set(&source, target, perturbations.unit_colors());
+        // Here, the attractor is the whole `z=1` subspace.
+        let subspace_control = perturbations.permanent_control_to_set(
+            &source,
+            &perturbations.mk_subspace(&[(z, true)]),
+            perturbations.unit_colors(),
+        );
+        assert_eq!(control.as_bdd(), subspace_control.as_bdd());
+
+        // Perturbing only `z` is sufficient.
+        let mut z_control = control.clone();
+        z_control.require_perturbation(z, Some(true));
+        for v in perturbations.variables().filter(|it| *it != z) {
+            z_control.exclude_perturbation(v, None);
+        }
+        assert_eq!(1.0, z_control.as_bdd().cardinality());
+
+        // A single attractor state results in (at most) the same controls.
+        let single_state = target.vertices().materialize().iter().next().unwrap();
+        let state_control =
+            perturbations.permanent_control(&source, &single_state, perturbations.unit_colors());
+        assert!(state_control
+            .as_colored_vertices()
+            .is_subset(control.as_colored_vertices()));
+    }
+
     /*
         #[test]
         pub fn test_trivial_permanent_all() {
@@ -0,0 +1,254 @@
+use crate::aeon::reachability::{backward, backward_within, forward_closed, forward_closed_until};
+use crate::control::{ControlMap, ControlMode, SequentialControlMap};
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
+use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
+use std::sync::OnceLock;
+
+impl PerturbationGraph {
+    /// Compute sequential control map. That is, a schedule of `conditions.len() + 1`
+    /// perturbations where the `i`-th perturbation is held until the network reaches the
+    /// `i`-th condition (e.g. an intermediate subspace or a trap set), and the last one is
+    /// held as long as necessary and then released (as in temporary control).
+    ///
+    /// A phase ends in the first state of its condition, so this state must already allow
+    /// the remaining phases to reach the target. A phase can use the empty perturbation, hence
+    /// the result also covers shorter schedules.
+    pub fn sequential_control(
+        &self,
+        source: &ArrayBitVector,
+        conditions: &[GraphColoredVertices],
+        target: &GraphColoredVertices,
+        compute_params: &GraphColors,
+    ) -> SequentialControlMap {
+        self.sequential_control_from_set(
+            &self.vertex(source),
+            conditions,
+            target,
+            // For a single source state, both quantifiers are equivalent.
+            SourceQuantifier::Exists,
+            compute_params,
+        )
+    }
+
+    /// The same as `sequential_control`, but the first perturbation is applied in a set of
+    /// `sources` states. The `quantifier` decides whether the first perturbation must work from
+    /// every source state, or from at least one. The later perturbations can depend on the
+    /// state in which the previous condition was reached.
+    pub fn sequential_control_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        conditions: &[GraphColoredVertices],
+        target: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+        compute_params: &GraphColors,
+    ) -> SequentialControlMap {
+        /*
+           We go backwards from the last phase. The `goal` is the set of states (and original
+           colors) from which the remaining phases lead to the target. Initially, this is the
+           strong basin of the target in the original graph.
+
+           A phase is held until its condition is reached, so the perturbation must jump into
+           the perturbed strong basin of the condition states which are also `goal` states. The
+           phase ends in the first condition state, hence the paths of this basin stop in the
+           condition and must not visit condition states outside of `goal`. The states from which
+           such jump exists (for some perturbation) are then the `goal` of the previous phase.
+           The phase starts in the states of the previous condition (or in `sources`).
+        */
+        let target_set = target.intersect_colors(compute_params);
+        let original_weak_basin = backward(self.as_original(), &target_set);
+        let mut goal = forward_closed(self.as_original(), &original_weak_basin);
+        let target_colors = goal.colors();
+
+        let mut phases = Vec::new();
+        for phase in (0..(conditions.len() + 1)).rev() {
+            let perturbed_strong_basin = match conditions.get(phase) {
+                Some(condition) => {
+                    let condition = condition.intersect_colors(compute_params);
+                    let region = condition.intersect(&goal);
+                    let allowed = self
+                        .as_perturbed()
+                        .unit_colored_vertices()
+                        .minus(&condition);
+                    let perturbed_weak_basin =
+                        backward_within(self.as_perturbed(), &region, &allowed.union(&region));
+                    forward_closed_until(self.as_perturbed(), &perturbed_weak_basin, &region)
+                }
+                None => {
+                    let region = goal.intersect_colors(compute_params);
+                    let perturbed_weak_basin = backward(self.as_perturbed(), &region);
+                    forward_closed(self.as_perturbed(), &perturbed_weak_basin)
+                }
+            };
+            let can_jump_and_hold = if phase == 0 {
+                self.post_perturbation_from_set(sources, &perturbed_strong_basin, quantifier)
+            } else {
+                let starts = conditions[phase - 1].intersect_colors(compute_params);
+                self.post_perturbation_from_set(
+                    &starts,
+                    &perturbed_strong_basin,
+                    SourceQuantifier::Exists,
+                )
+            };
+            goal = self.forget_perturbations(&self.forget_values(&can_jump_and_hold, true));
+            phases.push(ControlMap {
+                perturbation_set: can_jump_and_hold,
+                context: self.clone(),
+                mode: ControlMode::Sequential,
+                colors: OnceLock::from(target_colors.clone()),
+                robustness_target: None,
+            });
+        }
+        phases.reverse();
+        SequentialControlMap { phases }
+    }
+
+    /// Existentially abstract all perturbation parameters (of variables and regulations).
+    fn forget_perturbations(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        let symbolic_context = self.as_symbolic_context();
+        let mut result = set.as_bdd().clone();
+        for var in self.variables() {
+            if let Some(parameter) = self.get_perturbation_parameter(var) {
+                let table = symbolic_context.get_explicit_function_table(parameter);
+                result = result.var_exists(table.symbolic_variables()[0]);
+            }
+        }
+        for (_, p_var) in self.edge_perturbation_vars() {
+            result = result.var_exists(p_var);
+        }
+        self.unit_colored_vertices()
+            .intersect(&self.empty_colored_vertices().copy(result))
+    }
+}
+
+impl SequentialControlMap {
+    /// The control maps of the individual phases, in the order in which they are applied.
+    ///
+    /// Every map uses `ControlMode::Sequential`. Only the last phase has the semantics of
+    /// temporary control. The intermediate phases are held until the first state of their
+    /// condition, not until the target is unavoidable, so their maps must not be used on
+    /// their own (e.g. as if they were the result of `temporary_control`).
+    pub fn phases(&self) -> &[ControlMap] {
+        &self.phases
+    }
+
+    pub fn num_phases(&self) -> usize {
+        self.phases.len()
+    }
+
+    /// The number of original colors for which some schedule controls the network.
+    pub fn controllable_colors_cardinality(&self) -> f64 {
+        self.phases
+            .first()
+            .map(|it| it.controllable_colors_cardinality())
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::ControlMode;
+    use crate::perturbation::PerturbationGraph;
+    use crate::tests::myeloid_fixtures::{attractor_states, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn test_sequential_control_latch() {
+        // `Y` latches once `X` is active, `Z` latches once `Y` is active without `X`. Since `X`
+        // keeps its value, `Z` can only be activated by switching `X` on and then off.
+        let model = BooleanNetwork::try_from(
+            "X -> X\nX -> Y\nY -> Y\nY -> Z\nX -| Z\nZ -> Z\n$X: X\n$Y: Y | X\n$Z: Z | (Y & !X)",
+        )
+        .unwrap();
+        let x = model.as_graph().find_variable("X").unwrap();
+        let y = model.as_graph().find_variable("Y").unwrap();
+        let z = model.as_graph().find_variable("Z").unwrap();
+        let perturbations = PerturbationGraph::with_restricted_variables(&model, &[x]);
+        let source = ArrayBitVector::from(vec![false, false, false]);
+        let target = perturbations.mk_subspace(&[(z, true)]);
+
+        let temporary =
+            perturbations.temporary_control_to_set(&source, &target, perturbations.unit_colors());
+        assert!(temporary.as_colored_vertices().is_empty());
+
+        let condition = perturbations.mk_subspace(&[(y, true)]);
+        let control = perturbations.sequential_control(
+            &source,
+            &[condition],
+            &target,
+            perturbations.unit_colors(),
+        );
+        assert_eq!(2, control.num_phases());
+        assert_eq!(1.0, control.controllable_colors_cardinality());
+        let x_on = HashMap::from([(String::from("X"), true)]);
+        let first = control.phases()[0].minimal_perturbations();
+        assert_eq!(1, first.len());
+        assert_eq!(x_on, first[0].perturbation);
+        // The second phase starts in any state of the condition. After the first phase, `X`
+        // is still active and it has to be switched off.
+        let after_first = ArrayBitVector::from(vec![true, true, false]);
+        let second = perturbations
+            .post_perturbation(&after_first, control.phases()[1].as_colored_vertices());
+        assert!(!second.is_empty());
+        assert!(second.is_subset(&perturbations.fix_perturbation(x, Some(false))));
+    }
+
+    #[test]
+    pub fn test_sequential_control_first_condition_state() {
+        // The same latch as above, but once `X` is active, `W` can latch before `Y`. Then the
+        // first phase ends in a condition state where `Y` is inactive, and `Z` is unreachable.
+        let model = BooleanNetwork::try_from(
+            "X -> X\nX -> Y\nY -> Y\nX -> W\nW -> W\nY -> Z\nX -| Z\nZ -> Z\n\
+             $X: X\n$Y: Y | X\n$W: W | X\n$Z: Z | (Y & !X)",
+        )
+        .unwrap();
+        let x = model.as_graph().find_variable("X").unwrap();
+        let y = model.as_graph().find_variable("Y").unwrap();
+        let w = model.as_graph().find_variable("W").unwrap();
+        let z = model.as_graph().find_variable("Z").unwrap();
+        let perturbations = PerturbationGraph::with_restricted_variables(&model, &[x]);
+        let source = ArrayBitVector::from(vec![false, false, false, false]);
+        let target = perturbations.mk_subspace(&[(z, true)]);
+
+        let y_on = perturbations.mk_subspace(&[(y, true)]);
+        let control = perturbations.sequential_control(
+            &source,
+            std::slice::from_ref(&y_on),
+            &target,
+            perturbations.unit_colors(),
+        );
+        assert_eq!(1.0, control.controllable_colors_cardinality());
+
+        // Reaching `W` before `Y` ends the first phase outside of the basin of the target.
+        let y_or_w_on = y_on.union(&perturbations.mk_subspace(&[(w, true)]));
+        let control = perturbations.sequential_control(
+            &source,
+            &[y_or_w_on],
+            &target,
+            perturbations.unit_colors(),
+        );
+        assert_eq!(0.0, control.controllable_colors_cardinality());
+    }
+
+    #[test]
+    pub fn test_sequential_control_without_conditions_myeloid() {
+        // A single phase is the same as temporary control.
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let states = attractor_states(&perturbations);
+        let source = &states[0];
+        let target = perturbations.vertex(&states[1]);
+
+        let temporary =
+            perturbations.temporary_control_to_set(source, &target, perturbations.unit_colors());
+        let sequential =
+            perturbations.sequential_control(source, &[], &target, perturbations.unit_colors());
+        assert_eq!(1, sequential.num_phases());
+        assert_eq!(temporary.as_bdd(), sequential.phases()[0].as_bdd());
+        assert_eq!(ControlMode::Sequential, sequential.phases()[0].mode());
+    }
+}
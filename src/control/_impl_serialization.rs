@@ -0,0 +1,147 @@
+use crate::control::{ControlMap, ControlMode};
+use crate::error::ControlError;
+use crate::perturbation::_impl_serialization::{check_kind, invalid, read_json, write_json};
+use crate::perturbation::PerturbationGraph;
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+impl FromStr for ControlMode {
+    type Err = ControlError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "one-step" => Ok(ControlMode::OneStep),
+            "temporary" => Ok(ControlMode::Temporary),
+            "permanent" => Ok(ControlMode::Permanent),
+            "sequential" => Ok(ControlMode::Sequential),
+            _ => Err(invalid(&format!("Unknown control mode {:?}.", value))),
+        }
+    }
+}
+
+impl Display for ControlMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlMode::OneStep => write!(f, "one-step"),
+            ControlMode::Temporary => write!(f, "temporary"),
+            ControlMode::Permanent => write!(f, "permanent"),
+            ControlMode::Sequential => write!(f, "sequential"),
+        }
+    }
+}
+
+impl ControlMap {
+    /// Write this map to a JSON file. Besides the map itself, the file contains the model,
+    /// perturbable variables and the map `colors`, so that the map can be restored using
+    /// `ControlMap::load`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ControlError> {
+        write_json(path, &self.to_json())
+    }
+
+    /// Read a map created by `ControlMap::save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ControlMap, ControlError> {
+        let value = read_json(path)?;
+        check_kind(&value, "control-map")?;
+        let mode = value["mode"]
+            .as_str()
+            .ok_or_else(|| invalid("Missing control mode."))?
+            .parse::<ControlMode>()?;
+        let context = PerturbationGraph::try_from_json(&value["graph"])?;
+        let perturbation_set = context.try_bdd_from_json(&value["map"])?;
+        let colors = context.try_bdd_from_json(&value["colors"])?;
+        Ok(ControlMap {
+            perturbation_set: context.empty_colored_vertices().copy(perturbation_set),
+            colors: OnceLock::from(context.empty_colors().copy(colors)),
+            robustness_target: None,
+            context,
+            mode,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "control-map",
+            "mode": self.mode.to_string(),
+            "graph": self.context.to_json(),
+            "map": self.perturbation_set.as_bdd().to_string(),
+            "colors": self.colors().as_bdd().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::{ControlMap, ControlMode};
+    use crate::error::ControlError;
+    use crate::experiment_utils::get_all_params_with_attractor;
+    use crate::perturbation::_impl_serialization::{read_json, write_json};
+    use crate::perturbation::PerturbationGraph;
+    use crate::tests::myeloid_fixtures::{attractor_states, myeloid_witness};
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_save_load_control_map_myeloid() {
+        let model = myeloid_witness();
+        let gata1 = model.as_graph().find_variable("GATA1").unwrap();
+        let pu1 = model.as_graph().find_variable("PU1").unwrap();
+        let perturbations = PerturbationGraph::try_with_perturbation_values(
+            &model,
+            &[(gata1, None), (pu1, Some(false))],
+        )
+        .unwrap();
+        let states = attractor_states(&perturbations);
+        let attractor_colors = get_all_params_with_attractor(&perturbations, &states[1]);
+        let control = perturbations.permanent_control(&states[0], &states[1], &attractor_colors);
+
+        let path = std::env::temp_dir().join("pbn_control_test_control_map.json");
+        control.save(&path).unwrap();
+        let loaded = ControlMap::load(&path).unwrap();
+        assert_eq!(control.colors(), loaded.colors());
+
+        // The map colors and perturbable regulations are required.
+        let value = read_json(&path).unwrap();
+        let mut without_colors = value.clone();
+        without_colors.as_object_mut().unwrap().remove("colors");
+        write_json(&path, &without_colors).unwrap();
+        assert!(matches!(
+            ControlMap::load(&path),
+            Err(ControlError::InvalidControlMap(_))
+        ));
+        let mut without_edges = value;
+        without_edges["graph"]
+            .as_object_mut()
+            .unwrap()
+            .remove("perturbable_edges");
+        write_json(&path, &without_edges).unwrap();
+        assert!(matches!(
+            ControlMap::load(&path),
+            Err(ControlError::InvalidControlMap(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(ControlMode::Permanent, loaded.mode());
+        assert_eq!(control.as_bdd(), loaded.as_bdd());
+        assert_eq!(
+            control.perturbations().count(),
+            loaded.perturbations().count()
+        );
+        let gata1_true = HashMap::from([(String::from("GATA1"), true)]);
+        assert_eq!(
+            control.robustness(&gata1_true),
+            loaded.robustness(&gata1_true)
+        );
+        assert_eq!(Some(false), loaded.context.get_perturbation_value(pu1));
+
+        assert!(matches!(
+            ControlMap::load("models/myeloid_witness.aeon"),
+            Err(ControlError::InvalidControlMap(_))
+        ));
+        assert!(matches!(
+            ControlMap::load("models/does_not_exist.json"),
+            Err(ControlError::Io(_))
+        ));
+    }
+}
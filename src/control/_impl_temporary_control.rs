@@ -1,8 +1,9 @@
 use crate::aeon::reachability::{backward, forward_closed};
-use crate::control::ControlMap;
-use crate::perturbation::PerturbationGraph;
+use crate::control::{ControlMap, ControlMode};
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
 use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColors;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
+use std::sync::OnceLock;
 
 impl PerturbationGraph {
     /// Compute temporary control map. That is, controls which work when a perturbation is applied,
@@ -12,20 +13,54 @@ impl PerturbationGraph {
         source: &ArrayBitVector,
         target: &ArrayBitVector,
         compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.temporary_control_to_set(source, &self.vertex(target), compute_params)
+    }
+
+    /// The same as `temporary_control`, but the `target` is a set of states, for example a whole
+    /// (complex) attractor or a subspace (see `PerturbationGraph::mk_subspace`).
+    pub fn temporary_control_to_set(
+        &self,
+        source: &ArrayBitVector,
+        target: &GraphColoredVertices,
+        compute_params: &GraphColors,
+    ) -> ControlMap {
+        self.temporary_control_from_set(
+            &self.vertex(source),
+            target,
+            // For a single source state, both quantifiers are equivalent.
+            SourceQuantifier::Exists,
+            compute_params,
+        )
+    }
+
+    /// The same as `temporary_control_to_set`, but the perturbation is applied in a set of
+    /// `sources` states. The `quantifier` decides whether the perturbation must work from
+    /// every source state, or from at least one.
+    pub fn temporary_control_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        target: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+        compute_params: &GraphColors,
     ) -> ControlMap {
         /*
            Temporary control is the most challenging, because the control jump needs to be into
            the perturbed basin of a normal basin of target.
         */
-        let target_set = self.vertex(target).intersect_colors(compute_params);
+        let target_set = target.intersect_colors(compute_params);
         let original_weak_basin = backward(self.as_original(), &target_set);
         let original_strong_basin = forward_closed(self.as_original(), &original_weak_basin);
         let perturbed_weak_basin = backward(self.as_perturbed(), &original_strong_basin);
         let perturbed_strong_basin = forward_closed(self.as_perturbed(), &perturbed_weak_basin);
-        let can_jump_and_hold = self.post_perturbation(source, &perturbed_strong_basin);
+        let can_jump_and_hold =
+            self.post_perturbation_from_set(sources, &perturbed_strong_basin, quantifier);
         ControlMap {
             perturbation_set: can_jump_and_hold,
             context: self.clone(),
+            mode: ControlMode::Temporary,
+            colors: OnceLock::from(original_strong_basin.colors()),
+            robustness_target: None,
         }
     }
 }
@@ -1,11 +1,15 @@
 use crate::perturbation::PerturbationGraph;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 pub mod _impl_one_step_control;
 pub mod _impl_permanent_control;
+pub mod _impl_sequential_control;
 pub mod _impl_temporary_control;
 
 mod _impl_control_map;
+mod _impl_serialization;
 
 /// A mapping between admissible perturbations and colors for which the perturbation controls
 /// the network.
@@ -14,6 +18,53 @@ mod _impl_control_map;
 /// cannot outlive the graph.
 #[derive(Clone)]
 pub struct ControlMap {
-    context: PerturbationGraph,
-    perturbation_set: GraphColoredVertices,
+    pub(crate) context: PerturbationGraph,
+    pub(crate) perturbation_set: GraphColoredVertices,
+    pub(crate) mode: ControlMode,
+    /// The colors against which robustness is measured (see `ControlMap::colors`).
+    pub(crate) colors: OnceLock<GraphColors>,
+    /// If the `colors` are not known yet, they are computed from the strong basin of this
+    /// target in the original graph on first use.
+    pub(crate) robustness_target: Option<GraphColoredVertices>,
+}
+
+/// A schedule of perturbations applied one after another, where each phase is held until
+/// the network reaches the condition of the phase (see `PerturbationGraph::sequential_control`).
+///
+/// The map of each phase contains the jump states, colors and perturbations of that phase
+/// which lead to a successful completion of the remaining phases. The later phases start
+/// in any state of the previous condition. See `SequentialControlMap::phases` for the
+/// control mode of the individual maps.
+#[derive(Clone)]
+pub struct SequentialControlMap {
+    pub(crate) phases: Vec<ControlMap>,
+}
+
+/// The kind of control (i.e. how long the perturbation is applied) used to compute a map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlMode {
+    /// The perturbation is applied for a single time step.
+    OneStep,
+    /// The perturbation is held as long as necessary and then released.
+    Temporary,
+    /// The perturbation is never released.
+    Permanent,
+    /// One phase of a sequential control (see `PerturbationGraph::sequential_control`). The
+    /// perturbation is held until the condition of the next phase is reached.
+    Sequential,
+}
+
+/// A single perturbation contained in a `ControlMap`, together with the colors for which
+/// it controls the network.
+#[derive(Clone)]
+pub struct ControlPerturbation {
+    /// Perturbed variables (by name) and the values they are fixed to.
+    pub perturbation: HashMap<String, bool>,
+    /// Perturbed regulations (regulator and target names), in the order of
+    /// `PerturbationGraph::edge_perturbations`, together with the value to which the regulator
+    /// is frozen (see `PerturbationGraph::get_edge_perturbation_value`).
+    pub edges: Vec<(String, String, bool)>,
+    /// The colors for which the perturbation works. The perturbation parameters are fixed
+    /// to match `perturbation` and `edges`.
+    pub working_colors: GraphColors,
 }
@@ -0,0 +1,82 @@
+use biodivine_lib_param_bn::VariableId;
+use std::fmt::{Display, Formatter};
+
+/// Errors reported by the fallible (`try_*`) entry points of this crate.
+///
+/// Some of these entry points also have an older panicking variant without the `try_` prefix.
+/// Those are kept only for compatibility with existing code and must not be used on
+/// untrusted input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControlError {
+    /// The network could not be converted into a symbolic graph.
+    InvalidNetwork(String),
+    /// A variable name does not appear in the network.
+    UnknownVariable(String),
+    /// A variable id is out of range for the network.
+    InvalidVariableId(VariableId),
+    /// The network has no regulation between the two variables (regulator, target).
+    UnknownRegulation(String, String),
+    /// The variable exists, but it has no perturbation parameter.
+    NotPerturbable(String),
+    /// At least one perturbable variable is required.
+    NoPerturbableVariables,
+    /// The string does not describe a supported attractor search method.
+    UnknownAttractorSearch(String),
+    /// The phenotype membership is not valid (e.g. a fraction outside of `0.0..=1.0`).
+    InvalidPhenotypeMembership(String),
+    /// The experiment file does not have the expected `#source:`/`#target:` header.
+    InvalidExperiment(String),
+    /// The string is not a valid state vector (e.g. `[True, False]`).
+    InvalidState(String),
+    /// The phenotype is not a valid Boolean formula over network variables.
+    InvalidPhenotype(String),
+    /// A file cannot be read or written.
+    Io(String),
+    /// The file does not contain a valid serialized control map.
+    InvalidControlMap(String),
+    /// The experiment configuration is not valid.
+    InvalidConfig(String),
+}
+
+impl Display for ControlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlError::InvalidNetwork(message) => write!(f, "Invalid network: {}", message),
+            ControlError::UnknownVariable(name) => write!(f, "Unknown variable {:?}", name),
+            ControlError::InvalidVariableId(id) => write!(f, "Invalid variable id {:?}", id),
+            ControlError::UnknownRegulation(regulator, target) => {
+                write!(f, "Unknown regulation {:?} -> {:?}", regulator, target)
+            }
+            ControlError::NotPerturbable(name) => write!(
+                f,
+                "Variable {:?} does not have a perturbation parameter",
+                name
+            ),
+            ControlError::NoPerturbableVariables => {
+                write!(f, "At least one perturbable variable is required")
+            }
+            ControlError::UnknownAttractorSearch(method) => {
+                write!(f, "Unknown attractor search method {:?}", method)
+            }
+            ControlError::InvalidPhenotypeMembership(message) => {
+                write!(f, "Invalid phenotype membership: {}", message)
+            }
+            ControlError::InvalidExperiment(message) => {
+                write!(f, "Invalid experiment: {}", message)
+            }
+            ControlError::InvalidState(message) => write!(f, "Invalid state: {}", message),
+            ControlError::InvalidPhenotype(message) => {
+                write!(f, "Invalid phenotype: {}", message)
+            }
+            ControlError::Io(message) => write!(f, "I/O error: {}", message),
+            ControlError::InvalidControlMap(message) => {
+                write!(f, "Invalid control map: {}", message)
+            }
+            ControlError::InvalidConfig(message) => {
+                write!(f, "Invalid configuration: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ControlError {}
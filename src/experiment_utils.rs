@@ -1,5 +1,6 @@
 use crate::aeon::reachability::backward;
 use crate::control::ControlMap;
+use crate::error::ControlError;
 use crate::perturbation::PerturbationGraph;
 use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
@@ -97,33 +98,55 @@ pub fn run_control_experiment<F>(
     print!("real {}\nuser ??\nsys ??\n", elapsed);
 }
 
+/// Panicking variant of `try_parse_experiment`.
 pub fn parse_experiment(file: &str) -> (ArrayBitVector, ArrayBitVector, BooleanNetwork) {
-    let lines = file.lines().collect::<Vec<_>>();
-    let source_line = &lines[0];
-    let target_line = &lines[1];
-    assert!(source_line.starts_with("#source:"));
-    assert!(target_line.starts_with("#target:"));
-    let source = string_to_state(&source_line[8..]);
-    let target = string_to_state(&target_line[8..]);
-    (source, target, BooleanNetwork::try_from(file).unwrap())
+    try_parse_experiment(file).unwrap_or_else(|e| panic!("{}", e))
 }
 
-/// Convert a bit-vector string to an actual bit-vector.
+/// Read the `#source:` and `#target:` states and the model of an experiment file.
+pub fn try_parse_experiment(
+    file: &str,
+) -> Result<(ArrayBitVector, ArrayBitVector, BooleanNetwork), ControlError> {
+    let mut lines = file.lines();
+    let source_line = lines.next().unwrap_or("");
+    let target_line = lines.next().unwrap_or("");
+    let source = source_line.strip_prefix("#source:").ok_or_else(|| {
+        ControlError::InvalidExperiment(format!("Expected `#source:`, found {:?}", source_line))
+    })?;
+    let target = target_line.strip_prefix("#target:").ok_or_else(|| {
+        ControlError::InvalidExperiment(format!("Expected `#target:`, found {:?}", target_line))
+    })?;
+    let source = try_string_to_state(source)?;
+    let target = try_string_to_state(target)?;
+    let network = BooleanNetwork::try_from(file).map_err(ControlError::InvalidNetwork)?;
+    Ok((source, target, network))
+}
+
+/// Panicking variant of `try_string_to_state`.
 pub fn string_to_state(string_vector: &str) -> ArrayBitVector {
-    assert!(string_vector.starts_with('['));
-    assert!(string_vector.ends_with(']'));
-    let string_vector = &string_vector[1..(string_vector.len() - 1)];
+    try_string_to_state(string_vector).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Convert a bit-vector string to an actual bit-vector.
+pub fn try_string_to_state(string_vector: &str) -> Result<ArrayBitVector, ControlError> {
+    let inner = string_vector
+        .strip_prefix('[')
+        .and_then(|it| it.strip_suffix(']'))
+        .ok_or_else(|| ControlError::InvalidState(string_vector.to_string()))?;
     let mut vector = Vec::new();
-    for segment in string_vector.split(',') {
+    for segment in inner.split(',') {
         if segment.trim() == "True" {
             vector.push(true);
         } else if segment.trim() == "False" {
             vector.push(false);
         } else {
-            panic!("Unexpected: {}", segment);
+            return Err(ControlError::InvalidState(format!(
+                "Unexpected: {}",
+                segment
+            )));
         }
     }
-    ArrayBitVector::from(vector)
+    Ok(ArrayBitVector::from(vector))
 }
 
 /// Compute all graph colors for which the given state is an attractor state.
@@ -136,7 +159,7 @@ pub fn get_all_params_with_attractor(
     let mut attractor = seed;
     'forward: loop {
         if cfg!(feature = "print_progress") && attractor.as_bdd().size() > 100_000 {
-            println!("FWD-attractor: {}", attractor.as_bdd().size());
+            eprintln!("FWD-attractor: {}", attractor.as_bdd().size());
         }
         for var in graph.as_original().variables().rev() {
             let step = graph
@@ -155,3 +178,33 @@ pub fn get_all_params_with_attractor(
         return attractor.colors();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ControlError;
+    use crate::experiment_utils::{try_parse_experiment, try_string_to_state};
+    use biodivine_lib_param_bn::biodivine_std::bitvector::BitVector;
+
+    #[test]
+    pub fn test_parse_malformed_experiment() {
+        let state = try_string_to_state("[True, False, True]").unwrap();
+        assert_eq!(vec![true, false, true], state.values());
+
+        assert!(matches!(
+            try_string_to_state("True, False"),
+            Err(ControlError::InvalidState(_))
+        ));
+        assert!(matches!(
+            try_string_to_state("[True, Maybe]"),
+            Err(ControlError::InvalidState(_))
+        ));
+        assert!(matches!(
+            try_parse_experiment("#target:[True]\n#source:[False]\n"),
+            Err(ControlError::InvalidExperiment(_))
+        ));
+        assert!(matches!(
+            try_parse_experiment("#source:[True]\n#target:[Nope]\n"),
+            Err(ControlError::InvalidState(_))
+        ));
+    }
+}
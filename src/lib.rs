@@ -1,5 +1,8 @@
 pub mod control;
 
+/// Error type reported by the fallible entry points of this crate.
+pub mod error;
+
 /// "Standard" algorithms mostly adapted from Aeon.
 pub mod aeon;
 
@@ -14,4 +17,7 @@ pub mod experiment_utils;
 
 pub mod phenotype_control;
 
+/// Machine-readable (JSON) reports of control results.
+pub mod report;
+
 pub mod tests;
@@ -1,10 +1,20 @@
-use biodivine_lib_param_bn::{BooleanNetwork, FnUpdate, ParameterId, RegulatoryGraph, VariableId};
+use crate::error::ControlError;
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    RegulationConstraint, SymbolicAsyncGraph, SymbolicContext,
+};
+use biodivine_lib_param_bn::{
+    BooleanNetwork, FnUpdate, Monotonicity, ParameterId, Regulation, RegulatoryGraph, VariableId,
+};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 
-/// Create a copy of the given `model`, but make every regulation non-observable, and add an
-/// unspecified auto-regulation to each variable (that does not have an autoregulation already).
-/// Additionally, convert every implicit update function to and explicit parameter.
+/// Create a copy of the given `model`, but convert every implicit update function to an
+/// explicit parameter.
+///
+/// The observability and monotonicity of the existing regulations is preserved. They are
+/// later applied to the unit sets of the original and the perturbed graph (see
+/// `make_regulation_constraints`).
 ///
 /// This is a necessary pre-processing step before creating a `PerturbationGraph`.
 pub fn normalize_network(network: &BooleanNetwork) -> BooleanNetwork {
@@ -28,10 +38,12 @@ pub fn normalize_network(network: &BooleanNetwork) -> BooleanNetwork {
                 .unwrap();
         }
     }
+    network
+}
 
-    let network = network;
-
-    // Copy graph, but with non-observable regulations.
+/// Create a copy of the given `network` where every regulation is non-observable and
+/// without monotonicity. The parameters and update functions stay the same.
+pub fn relax_regulations(network: &BooleanNetwork) -> BooleanNetwork {
     let mut result = RegulatoryGraph::new(
         network
             .variables()
@@ -44,21 +56,62 @@ pub fn normalize_network(network: &BooleanNetwork) -> BooleanNetwork {
                 network.get_variable_name(regulation.get_regulator()),
                 network.get_variable_name(regulation.get_target()),
                 false,
-                regulation.get_monotonicity(),
+                None,
             )
             .unwrap();
     }
-    // Copy variables and regulations.
-    for v in result.variables() {
-        if result.find_regulation(v, v).is_none() {
-            let name = result.get_variable_name(v).clone();
-            result
-                .add_regulation(name.as_str(), name.as_str(), false, None)
-                .unwrap();
+    with_regulatory_graph(network, result)
+}
+
+/// Create a copy of the given `network` where the observability or monotonicity of
+/// a regulation is removed if no update function can satisfy it (e.g. an observable
+/// regulation of a constant function). The relaxed regulations (regulator, target) are
+/// returned in the second item of the result, in sorted order.
+pub fn relax_unsatisfiable_regulations(
+    network: &BooleanNetwork,
+) -> Result<(BooleanNetwork, Vec<(VariableId, VariableId)>), ControlError> {
+    let normalized = normalize_network(network);
+    let context = SymbolicContext::new(&relax_regulations(&normalized))
+        .map_err(ControlError::InvalidNetwork)?;
+    let mut result = RegulatoryGraph::new(
+        network
+            .variables()
+            .map(|it| network.get_variable_name(it).clone())
+            .collect(),
+    );
+    let mut relaxed = Vec::new();
+    for regulation in normalized.as_graph().regulations() {
+        let (observability, monotonicity) =
+            regulation_constraints(&normalized, &context, regulation);
+        let is_observable =
+            regulation.is_observable() && !observability.is_some_and(|it| it.is_false());
+        let monotonicity = if monotonicity.is_some_and(|it| it.is_false()) {
+            None
+        } else {
+            regulation.get_monotonicity()
+        };
+        if is_observable != regulation.is_observable()
+            || monotonicity != regulation.get_monotonicity()
+        {
+            relaxed.push((regulation.get_regulator(), regulation.get_target()));
         }
+        result
+            .add_regulation(
+                network.get_variable_name(regulation.get_regulator()),
+                network.get_variable_name(regulation.get_target()),
+                is_observable,
+                monotonicity,
+            )
+            .unwrap();
     }
+    relaxed.sort();
+    Ok((with_regulatory_graph(network, result), relaxed))
+}
 
-    let mut result = BooleanNetwork::new(result);
+/// Create a copy of the given `network` with a different regulatory `graph` (with the same
+/// variables). The parameters and update functions stay the same.
+fn with_regulatory_graph(network: &BooleanNetwork, graph: RegulatoryGraph) -> BooleanNetwork {
+    let mut result = BooleanNetwork::new(graph);
 
     // Copy parameters.
     for p in network.parameters() {
@@ -71,23 +124,134 @@ pub fn normalize_network(network: &BooleanNetwork) -> BooleanNetwork {
     // Copy update functions.
     for v in result.variables() {
         // Technically, the models should have equivalent variable ids, so this should work.
-        // Also, we already replaced all missing functions with parameters.
-        let function = network.get_update_function(v).clone().unwrap();
-        result.add_update_function(v, function).unwrap();
+        if let Some(function) = network.get_update_function(v) {
+            result.add_update_function(v, function.clone()).unwrap();
+        }
     }
 
     result
 }
 
-/// "Original" network contains the same parameters as perturbed network, but the parameters
-/// actually do not matter. They are present only to ensure the two networks have the same
-/// symbolic encoding.
+/// The observability and the monotonicity constraint of the `regulation` of the `network`
+/// (`None` if the regulation does not have one), encoded using the `context` of
+/// `relax_regulations(network)`.
+fn regulation_constraints(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+    regulation: &Regulation,
+) -> (Option<Bdd>, Option<Bdd>) {
+    // Same as in `SymbolicAsyncGraph`, a regulation only has to be observable for some value
+    // of the input parameters and monotonous for all of them.
+    let inputs = network
+        .parameters()
+        .filter(|it| network[*it].get_arity() == 0)
+        .map(|it| context.get_explicit_function_table(it).symbolic_variables()[0])
+        .collect::<Vec<_>>();
+    let regulator = regulation.get_regulator();
+    // We assume the function exists -- substituted implicit functions in normalization.
+    let fn_is_true = context.mk_fn_update_true(
+        network
+            .get_update_function(regulation.get_target())
+            .as_ref()
+            .unwrap(),
+    );
+
+    let observability = regulation.is_observable().then(|| {
+        RegulationConstraint::mk_observability(context, &fn_is_true, regulator).exists(&inputs)
+    });
+    let monotonicity = match regulation.get_monotonicity() {
+        Some(Monotonicity::Activation) => Some(
+            RegulationConstraint::mk_activation(context, &fn_is_true, regulator).for_all(&inputs),
+        ),
+        Some(Monotonicity::Inhibition) => Some(
+            RegulationConstraint::mk_inhibition(context, &fn_is_true, regulator).for_all(&inputs),
+        ),
+        None => None,
+    };
+    (observability, monotonicity)
+}
+
+/// Compute the colors of the `original` network (see `make_original_network`) which satisfy
+/// the observability and monotonicity of its regulations, encoded using the `context` of
+/// `relax_regulations(original)`.
+///
+/// The first item of the result is the unit set of the original graph, where every constraint
+/// applies. The original graph describes the model itself: the update function of a perturbed
+/// variable is still used before the perturbation is applied and after it is released.
+///
+/// The second item is the unit set of the perturbed graph (see `make_perturbed_graph`). Here,
+/// a constraint only applies to the colors where its regulation is not cut by a perturbation,
+/// i.e. where neither the target (`perturbation_parameters`) nor the regulation itself
+/// (`edge_perturbation_parameters`) is perturbed. In the remaining colors, the update function
+/// of the target is not restricted by the regulation. The sets of a `PerturbationGraph` are
+/// created from the unit set of the original graph, so the results only contain colors of
+/// the model.
+///
+/// Returns an error if no update function satisfies one of the constraints (use
+/// `relax_unsatisfiable_regulations` to remove such constraints), or if the constraints
+/// are not satisfiable together.
+pub fn make_regulation_constraints(
+    original: &BooleanNetwork,
+    context: &SymbolicContext,
+    perturbation_parameters: &HashMap<VariableId, ParameterId>,
+    edge_perturbation_parameters: &HashMap<(VariableId, VariableId), ParameterId>,
+) -> Result<(Bdd, Bdd), ControlError> {
+    let mut original_unit = context.mk_constant(true);
+    let mut perturbed_unit = context.mk_constant(true);
+    for regulation in original.as_graph().regulations() {
+        let regulator = regulation.get_regulator();
+        let target = regulation.get_target();
+        // The regulation is cut if its target or the regulation itself is perturbed.
+        let mut is_cut = context.mk_constant(false);
+        for parameter in perturbation_parameters
+            .get(&target)
+            .into_iter()
+            .chain(edge_perturbation_parameters.get(&(regulator, target)))
+        {
+            is_cut = is_cut.or(&context.mk_fn_update_true(&FnUpdate::mk_param(*parameter, &[])));
+        }
+
+        let (observability, monotonicity) = regulation_constraints(original, context, regulation);
+        for (name, constraint) in [
+            ("observability", observability),
+            ("monotonicity", monotonicity),
+        ] {
+            let Some(constraint) = constraint else {
+                continue;
+            };
+            if constraint.is_false() {
+                return Err(ControlError::InvalidNetwork(format!(
+                    "The {} of regulation {} -> {} cannot be satisfied by any update function.",
+                    name,
+                    original.get_variable_name(regulator),
+                    original.get_variable_name(target),
+                )));
+            }
+            original_unit = original_unit.and(&constraint);
+            perturbed_unit = perturbed_unit.and(&is_cut.or(&constraint));
+        }
+    }
+    if original_unit.is_false() {
+        return Err(ControlError::InvalidNetwork(
+            "The regulation constraints of the network are not satisfiable together.".to_string(),
+        ));
+    }
+    Ok((original_unit, perturbed_unit))
+}
+
+/// "Original" network contains the perturbation parameters, but the parameters actually do not
+/// matter. They are present only to ensure the symbolic encoding can also represent the
+/// perturbed graph (see `make_perturbed_graph`).
 ///
 /// The `perturb` parameter specifies which variables should be actually subject to perturbations.
+/// Similarly, `perturb_edges` specifies the regulations (regulator, target) which can be
+/// perturbed. The parameters of perturbed regulations are stored in `edge_perturbation_parameters`.
 pub fn make_original_network(
     network: &BooleanNetwork,
     perturbation_parameters: &mut HashMap<VariableId, ParameterId>,
+    edge_perturbation_parameters: &mut HashMap<(VariableId, VariableId), ParameterId>,
     perturb: &[VariableId],
+    perturb_edges: &[(VariableId, VariableId)],
 ) -> BooleanNetwork {
     let mut result = BooleanNetwork::new(network.as_graph().clone());
 
@@ -103,81 +267,122 @@ pub fn make_original_network(
         );
     }
 
+    // Set uncontrolled function to (p || !p) && f(...) for every control parameter p of v.
+    // (The function has to *contain* the parameter to ensure it is part of the encoding)
+    let with_tautology = |function: FnUpdate, parameter_id: ParameterId| {
+        // A little trick to avoid always cloning the value of fn_parameter...
+        let fn_parameter = || FnUpdate::mk_param(parameter_id, &[]);
+        let control_tautology = fn_parameter().or(FnUpdate::mk_not(fn_parameter()));
+        control_tautology.and(function)
+    };
+
     // Then add control parameters and modify update functions:
     for v in network.variables().rev() {
         // We assume the function exists -- substituted implicit functions in normalization.
-        let function = network.get_update_function(v).as_ref().unwrap();
+        let mut function = network.get_update_function(v).clone().unwrap();
 
         if perturb.contains(&v) {
             let v_perturbed = format!("{}_perturbed", network.get_variable_name(v));
             let parameter_id = result.add_parameter(v_perturbed.as_str(), 0).unwrap();
             perturbation_parameters.insert(v, parameter_id);
+            function = with_tautology(function, parameter_id);
+        }
 
-            // A little trick to avoid always cloning the value of fn_parameter...
-            let fn_parameter = || FnUpdate::mk_param(parameter_id, &[]);
-
-            // Set uncontrolled function to (v_perturbed || !v_perturbed) && f(...)
-            // (The function has to *contain* the parameter to ensure the encoding is the same)
-            let control_tautology = fn_parameter().or(FnUpdate::mk_not(fn_parameter()));
-            let uncontrolled_function = control_tautology.and(function.clone());
-            result
-                .add_update_function(v, uncontrolled_function)
-                .unwrap();
-        } else {
-            // If not perturbed, just copy what we have.
-            result.add_update_function(v, function.clone()).unwrap();
+        for (regulator, _) in perturb_edges.iter().filter(|(_, target)| *target == v) {
+            let edge_perturbed = format!(
+                "{}_{}_edge_perturbed",
+                network.get_variable_name(*regulator),
+                network.get_variable_name(v)
+            );
+            let parameter_id = result.add_parameter(edge_perturbed.as_str(), 0).unwrap();
+            edge_perturbation_parameters.insert((*regulator, v), parameter_id);
+            function = with_tautology(function, parameter_id);
         }
+
+        result.add_update_function(v, function).unwrap();
     }
 
     result
 }
 
-/// "Perturbed" network contains extra parameters which make it possible to disable perturbed
-/// update functions (i.e. if the parameter is true, the network does not change that variable).
+/// Create the symbolic graph of the "perturbed" network from the `original_graph` (see
+/// `make_original_network`). Here, the perturbation parameters make it possible to disable
+/// perturbed update functions (i.e. if the parameter is true, the network does not change
+/// that variable). The parameters of perturbed regulations replace the regulator with its
+/// value from `edge_perturbation_values` in the update function of the target.
 ///
-/// The `perturb` parameter specifies which variables should be actually subject to perturbations.
-pub fn make_perturbed_network(
-    network: &BooleanNetwork,
-    perturbation_parameters: &mut HashMap<VariableId, ParameterId>,
-    perturb: &[VariableId],
-) -> BooleanNetwork {
-    let mut result = BooleanNetwork::new(network.as_graph().clone());
+/// The perturbed network has the regulations and parameters of the network of
+/// `original_graph`, so the parameters keep their arity and the graph can reuse the same
+/// symbolic context. A non-observable auto-regulation is only added to the perturbed
+/// variables which do not have one. The graph uses the given `unit` colors (where the regulation
+/// constraints only apply to the regulations which are not cut by a perturbation, see
+/// `make_regulation_constraints`), restricted to the states where every variable from
+/// `perturbation_values` is perturbed only to its allowed value.
+pub fn make_perturbed_graph(
+    original_graph: &SymbolicAsyncGraph,
+    unit: Bdd,
+    perturbation_parameters: &HashMap<VariableId, ParameterId>,
+    perturbation_values: &HashMap<VariableId, bool>,
+    edge_perturbation_parameters: &HashMap<(VariableId, VariableId), ParameterId>,
+    edge_perturbation_values: &HashMap<(VariableId, VariableId), bool>,
+) -> Result<SymbolicAsyncGraph, ControlError> {
+    // The original graph is created from `relax_regulations(original)`, which has no
+    // regulation constraints.
+    let original = original_graph.as_network().unwrap();
+    let context = original_graph.symbolic_context();
 
-    // First, copy existing parameters from the original network:
-    for old_id in network.parameters() {
-        let parameter = network.get_parameter(old_id);
-        let new_id = result
+    let mut graph = original.as_graph().clone();
+    let mut perturbed = perturbation_parameters.keys().cloned().collect::<Vec<_>>();
+    perturbed.sort();
+    for var in perturbed {
+        if graph.find_regulation(var, var).is_none() {
+            let name = original.get_variable_name(var);
+            graph.add_regulation(name, name, false, None).unwrap();
+        }
+    }
+    let mut network = BooleanNetwork::new(graph);
+    for p in original.parameters() {
+        let parameter = &original[p];
+        network
             .add_parameter(parameter.get_name(), parameter.get_arity())
             .unwrap();
-        assert_eq!(
-            new_id, old_id,
-            "This should not happen. Encodings should be the same."
-        );
     }
 
-    // Then add control parameters and modify update functions:
-    for v in network.variables().rev() {
+    let mut edges = edge_perturbation_parameters.iter().collect::<Vec<_>>();
+    edges.sort();
+    for var in original.variables() {
         // We assume the function exists -- substituted implicit functions in normalization.
-        let function = network.get_update_function(v).as_ref().unwrap();
-
-        if perturb.contains(&v) {
-            let v_perturbed = format!("{}_perturbed", network.get_variable_name(v));
-            let parameter_id = result.add_parameter(v_perturbed.as_str(), 0).unwrap();
-            perturbation_parameters.insert(v, parameter_id);
-            // A little trick to avoid always cloning the value of fn_parameter...
-            let fn_parameter = || FnUpdate::mk_param(parameter_id, &[]);
-
-            // Set controlled function to (v_perturbed => v) && (!v_perturbed => f(...))
-            let controlled_implies_v = fn_parameter().implies(FnUpdate::mk_var(v));
-            let not_controlled_implies_f =
-                FnUpdate::mk_not(fn_parameter()).implies(function.clone());
-            let controlled_function = controlled_implies_v.and(not_controlled_implies_f);
-            result.add_update_function(v, controlled_function).unwrap();
-        } else {
-            // If not perturbed, just copy what we have.
-            result.add_update_function(v, function.clone()).unwrap();
+        let mut function = original.get_update_function(var).clone().unwrap();
+        for ((regulator, _), parameter) in edges.iter().filter(|((_, it), _)| *it == var) {
+            // Perturbed regulation gives (e_perturbed => f[u = c]) && (!e_perturbed => f)
+            let is_perturbed = FnUpdate::mk_param(**parameter, &[]);
+            let value = FnUpdate::Const(edge_perturbation_values[&(*regulator, var)]);
+            let frozen = function.substitute_variable(*regulator, &value);
+            function = is_perturbed
+                .clone()
+                .implies(frozen)
+                .and(is_perturbed.negation().implies(function));
+        }
+        if let Some(parameter) = perturbation_parameters.get(&var) {
+            // Controlled function is (v_perturbed => v) && (!v_perturbed => f(...))
+            let is_perturbed = FnUpdate::mk_param(*parameter, &[]);
+            function = is_perturbed
+                .clone()
+                .implies(FnUpdate::mk_var(var))
+                .and(is_perturbed.negation().implies(function));
         }
+        network.add_update_function(var, function).unwrap();
     }
 
-    result
+    let mut unit = unit;
+    for (var, value) in perturbation_values {
+        // Restricted perturbation gives v_perturbed => (v = value)
+        let is_perturbed =
+            context.mk_fn_update_true(&FnUpdate::mk_param(perturbation_parameters[var], &[]));
+        let has_value = context.mk_state_variable_is_true(*var);
+        let has_value = if *value { has_value } else { has_value.not() };
+        unit = unit.and(&is_perturbed.imp(&has_value));
+    }
+    SymbolicAsyncGraph::with_custom_context(&network, context.clone(), unit)
+        .map_err(ControlError::InvalidNetwork)
 }
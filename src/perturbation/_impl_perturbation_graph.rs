@@ -1,7 +1,10 @@
-use crate::perturbation::PerturbationGraph;
+use crate::error::ControlError;
 use crate::perturbation::_algo_network_transformations::{
-    make_original_network, make_perturbed_network, normalize_network,
+    make_original_network, make_perturbed_graph, make_regulation_constraints, normalize_network,
+    relax_regulations, relax_unsatisfiable_regulations,
 };
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
+use biodivine_lib_bdd::BddVariable;
 use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::{
@@ -11,6 +14,7 @@ use biodivine_lib_param_bn::{BooleanNetwork, ParameterId, VariableId, VariableId
 use std::collections::HashMap;
 
 impl PerturbationGraph {
+    /// Create a new perturbation graph where every variable can be perturbed.
     pub fn new(network: &BooleanNetwork) -> PerturbationGraph {
         PerturbationGraph::with_restricted_variables(
             network,
@@ -18,32 +22,184 @@ impl PerturbationGraph {
         )
     }
 
-    /// Create a new perturbation graph for a given Boolean network.
+    /// Panicking variant of `try_with_restricted_variables`.
     pub fn with_restricted_variables(
         network: &BooleanNetwork,
         perturb: &[VariableId],
     ) -> PerturbationGraph {
+        Self::try_with_restricted_variables(network, perturb).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new perturbation graph for a given Boolean network, where only the `perturb`
+    /// variables can be perturbed.
+    pub fn try_with_restricted_variables(
+        network: &BooleanNetwork,
+        perturb: &[VariableId],
+    ) -> Result<PerturbationGraph, ControlError> {
+        let perturb = perturb.iter().map(|it| (*it, None)).collect::<Vec<_>>();
+        Self::try_with_perturbation_values(network, &perturb)
+    }
+
+    /// Create a new perturbation graph where each of the `perturb` variables can be perturbed
+    /// either to the given value only (e.g. `Some(false)` for knockout-only), or to any value
+    /// (`None`).
+    ///
+    /// Returns an error if one of the `perturb` variables does not belong to the network or
+    /// the network cannot be encoded symbolically.
+    pub fn try_with_perturbation_values(
+        network: &BooleanNetwork,
+        perturb: &[(VariableId, Option<bool>)],
+    ) -> Result<PerturbationGraph, ControlError> {
+        Self::try_with_edge_perturbations(network, perturb, &[])
+    }
+
+    /// Create a new perturbation graph where, in addition to the `perturb` variables (see
+    /// `try_with_perturbation_values`), the given regulations can be perturbed. A perturbed
+    /// regulation `(regulator, target, value)` fixes the input of the update function of
+    /// `target` to the given `value` of `regulator` (e.g. an edge knockout), while the
+    /// `regulator` itself keeps its dynamics.
+    ///
+    /// Returns an error if one of the variables does not belong to the network, one of the
+    /// `perturb_edges` is not a regulation of the network, or the network cannot be encoded
+    /// symbolically (e.g. one of its regulation constraints cannot be satisfied, see
+    /// `PerturbationGraph::relax_unsatisfiable_regulations`).
+    pub fn try_with_edge_perturbations(
+        network: &BooleanNetwork,
+        perturb: &[(VariableId, Option<bool>)],
+        perturb_edges: &[(VariableId, VariableId, bool)],
+    ) -> Result<PerturbationGraph, ControlError> {
+        let edge_variables = perturb_edges
+            .iter()
+            .flat_map(|(regulator, target, _)| [*regulator, *target]);
+        if let Some(invalid) = perturb
+            .iter()
+            .map(|(it, _)| *it)
+            .chain(edge_variables)
+            .find(|it| it.to_index() >= network.num_vars())
+        {
+            return Err(ControlError::InvalidVariableId(invalid));
+        }
+        if let Some((regulator, target, _)) = perturb_edges.iter().find(|(regulator, target, _)| {
+            network
+                .as_graph()
+                .find_regulation(*regulator, *target)
+                .is_none()
+        }) {
+            return Err(ControlError::UnknownRegulation(
+                network.get_variable_name(*regulator).clone(),
+                network.get_variable_name(*target).clone(),
+            ));
+        }
+        let perturbation_values = perturb
+            .iter()
+            .filter_map(|(var, value)| value.map(|value| (*var, value)))
+            .collect::<HashMap<_, _>>();
+        let perturb = perturb.iter().map(|(var, _)| *var).collect::<Vec<_>>();
+        let edge_perturbation_values = perturb_edges
+            .iter()
+            .map(|(regulator, target, value)| ((*regulator, *target), *value))
+            .collect::<HashMap<_, _>>();
+        let mut perturb_edges = edge_perturbation_values.keys().cloned().collect::<Vec<_>>();
+        perturb_edges.sort();
+
         let normalized = normalize_network(network);
 
-        let mut original_parameters = HashMap::new();
-        let mut perturbed_parameters = HashMap::new();
+        let mut perturbation_parameters = HashMap::new();
+        let mut edge_perturbation_parameters = HashMap::new();
+        let original = make_original_network(
+            &normalized,
+            &mut perturbation_parameters,
+            &mut edge_perturbation_parameters,
+            &perturb,
+            &perturb_edges,
+        );
+        // The regulation constraints are applied manually, such that they do not restrict
+        // the update functions of the regulations cut by a perturbation.
+        let relaxed = relax_regulations(&original);
+        let context = SymbolicContext::new(&relaxed).map_err(ControlError::InvalidNetwork)?;
+        let (original_unit, perturbed_unit) = make_regulation_constraints(
+            &original,
+            &context,
+            &perturbation_parameters,
+            &edge_perturbation_parameters,
+        )?;
+        let original_graph =
+            SymbolicAsyncGraph::with_custom_context(&relaxed, context, original_unit)
+                .map_err(ControlError::InvalidNetwork)?;
+        let perturbed_graph = make_perturbed_graph(
+            &original_graph,
+            perturbed_unit,
+            &perturbation_parameters,
+            &perturbation_values,
+            &edge_perturbation_parameters,
+            &edge_perturbation_values,
+        )?;
+
+        Ok(PerturbationGraph {
+            network: network.clone(),
+            original_graph,
+            perturbed_graph,
+            perturbation_parameters,
+            perturbation_values,
+            edge_perturbation_parameters,
+            edge_perturbation_values,
+        })
+    }
 
-        let original = make_original_network(&normalized, &mut original_parameters, perturb);
-        let perturbed = make_perturbed_network(&normalized, &mut perturbed_parameters, perturb);
+    /// Create a new perturbation graph where only the variables with the given names
+    /// can be perturbed.
+    pub fn try_with_perturbable_names(
+        network: &BooleanNetwork,
+        perturb: &[&str],
+    ) -> Result<PerturbationGraph, ControlError> {
+        let perturb = perturb
+            .iter()
+            .map(|name| {
+                network
+                    .as_graph()
+                    .find_variable(name)
+                    .ok_or_else(|| ControlError::UnknownVariable(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::try_with_restricted_variables(network, &perturb)
+    }
 
-        assert_eq!(original_parameters, perturbed_parameters);
+    /// The network from which this graph was created.
+    pub fn as_network(&self) -> &BooleanNetwork {
+        &self.network
+    }
 
-        PerturbationGraph {
-            original_graph: SymbolicAsyncGraph::new(&original).unwrap(),
-            perturbed_graph: SymbolicAsyncGraph::new(&perturbed).unwrap(),
-            perturbation_parameters: original_parameters,
-        }
+    /// Create a copy of the `network` without the observability or monotonicity of the
+    /// regulations which cannot be satisfied by any update function (e.g. an observable
+    /// regulation of a constant function). Such networks are rejected when creating
+    /// a perturbation graph, unless the constraints are removed this way first.
+    ///
+    /// The relaxed regulations (regulator, target) are returned in the second item of
+    /// the result, in sorted order.
+    pub fn relax_unsatisfiable_regulations(
+        network: &BooleanNetwork,
+    ) -> Result<(BooleanNetwork, Vec<(VariableId, VariableId)>), ControlError> {
+        relax_unsatisfiable_regulations(network)
     }
 
+    /// The graph where no perturbation is applied.
+    ///
+    /// The regulation constraints of the model are only applied to its unit set (see
+    /// `make_regulation_constraints`), hence all regulations of its `as_network` are
+    /// non-observable and without monotonicity. Use `as_network` to access the model.
     pub fn as_original(&self) -> &SymbolicAsyncGraph {
         &self.original_graph
     }
 
+    /// The graph where the perturbed variables and regulations are fixed.
+    ///
+    /// Its `as_network` is the perturbed network (see `make_perturbed_graph`), where
+    /// a perturbed variable keeps its value. Use `as_network` to access the model.
+    ///
+    /// Its unit set also contains the colors where a perturbation cuts a regulation and the
+    /// update function of the target violates the constraints of the regulation. These are
+    /// not colors of the model, so sets of this graph should be created from the sets of
+    /// the original graph (e.g. `PerturbationGraph::mk_unit_colored_vertices`).
     pub fn as_perturbed(&self) -> &SymbolicAsyncGraph {
         &self.perturbed_graph
     }
@@ -60,17 +216,72 @@ impl PerturbationGraph {
         self.perturbation_parameters.get(&variable).cloned()
     }
 
+    /// Return the only value to which the `variable` can be perturbed, or `None` if it can be
+    /// perturbed to both values (or cannot be perturbed at all).
+    pub fn get_perturbation_value(&self, variable: VariableId) -> Option<bool> {
+        self.perturbation_values.get(&variable).cloned()
+    }
+
     pub fn num_perturbation_parameters(&self) -> usize {
         self.perturbation_parameters.len()
     }
 
-    /*
-        WARNING: The unit color set in the perturbed graph is not correct! It enforces
-        observability and for a regulation to be observable, the variable cannot be perturbed.
-        So the unit set only contains one non-perturbed parametrisation.
+    pub fn get_edge_perturbation_parameter(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+    ) -> Option<ParameterId> {
+        self.edge_perturbation_parameters
+            .get(&(regulator, target))
+            .cloned()
+    }
+
+    /// Return the value to which the `regulator` is frozen when the regulation is perturbed,
+    /// or `None` if the regulation cannot be perturbed.
+    pub fn get_edge_perturbation_value(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+    ) -> Option<bool> {
+        self.edge_perturbation_values
+            .get(&(regulator, target))
+            .cloned()
+    }
+
+    /// The perturbable regulations (regulator, target), in sorted order.
+    pub fn edge_perturbations(&self) -> Vec<(VariableId, VariableId)> {
+        let mut result = self
+            .edge_perturbation_parameters
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        result.sort();
+        result
+    }
 
-        Consequently, we use the original graph where possible.
-    */
+    pub fn num_edge_perturbation_parameters(&self) -> usize {
+        self.edge_perturbation_parameters.len()
+    }
+
+    /// Symbolic variables of the parameters of perturbable regulations, in the order of
+    /// `PerturbationGraph::edge_perturbations`.
+    pub(crate) fn edge_perturbation_vars(&self) -> Vec<((VariableId, VariableId), BddVariable)> {
+        self.edge_perturbations()
+            .into_iter()
+            .map(|edge| {
+                let parameter = self.edge_perturbation_parameters[&edge];
+                let table = self
+                    .as_symbolic_context()
+                    .get_explicit_function_table(parameter);
+                (edge, table.symbolic_variables()[0])
+            })
+            .collect()
+    }
+
+    // The unit set of the perturbed graph is a subset of the unit set of the original graph
+    // (see `make_perturbed_graph`), so the sets below are valid in both graphs. Only the
+    // states where a variable is perturbed to a value that is not allowed for it are
+    // missing from the perturbed graph.
 
     pub fn empty_colors(&self) -> &GraphColors {
         self.original_graph.empty_colors()
@@ -112,6 +323,16 @@ impl PerturbationGraph {
         self.original_graph.fix_network_variable(variable, value)
     }
 
+    /// Return the subspace of all vertices (and colors) where the given variables have the
+    /// given values.
+    pub fn mk_subspace(&self, values: &[(VariableId, bool)]) -> GraphColoredVertices {
+        let mut result = self.mk_unit_colored_vertices();
+        for (variable, value) in values {
+            result = result.intersect(&self.fix_variable(*variable, *value));
+        }
+        result
+    }
+
     pub fn strong_basin(&self, target: &ArrayBitVector) -> GraphColoredVertices {
         let target_set = self.vertex(target);
         let weak_basin = crate::aeon::reachability::backward(self.as_original(), &target_set);
@@ -124,17 +345,18 @@ impl PerturbationGraph {
     ///
     /// If no value is given, return vertices and colors where the variable is perturbed.
     ///
-    /// If the value cannot be perturbed, return empty set.
+    /// If the variable cannot be perturbed (to the given value), return empty set.
     pub fn fix_perturbation(
         &self,
         variable: VariableId,
         value: Option<bool>,
     ) -> GraphColoredVertices {
         if let Some(is_perturbed) = self.perturbation_parameters.get(&variable) {
+            // States of the perturbed graph only contain the allowed values of `variable`.
             let states = if let Some(value) = value {
-                self.fix_variable(variable, value)
+                self.as_perturbed().fix_network_variable(variable, value)
             } else {
-                self.mk_unit_colored_vertices()
+                self.as_perturbed().mk_unit_colored_vertices()
             };
             let bdd_is_perturbed = self
                 .as_symbolic_context()
@@ -157,8 +379,6 @@ impl PerturbationGraph {
             if !function.is_none() {
                 continue;
             }
-            println!("{:?}", bn.get_variable_name(v));
-
             let v_name = bn.get_variable_name(v);
             let value = values.get(v_name).cloned().unwrap_or(false);
 
@@ -190,12 +410,42 @@ impl PerturbationGraph {
             let bdd_is_perturbed = self
                 .as_symbolic_context()
                 .mk_uninterpreted_function_is_true(*is_perturbed, &[]);
-            self.unit_colors().copy(bdd_is_perturbed.not())
+            // The unit colors are not a full cube (see `make_regulation_constraints`).
+            self.mk_unit_colors()
+                .minus(&self.unit_colors().copy(bdd_is_perturbed))
         } else {
             self.mk_unit_colors()
         }
     }
 
+    /// Return a subset of vertices and colors where the regulation from `regulator` to `target`
+    /// is perturbed (i.e. `target` reads the fixed value of `regulator`).
+    ///
+    /// If the regulation cannot be perturbed, return empty set.
+    pub fn fix_edge_perturbation(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+    ) -> GraphColoredVertices {
+        if let Some(is_perturbed) = self.edge_perturbation_parameters.get(&(regulator, target)) {
+            let bdd_is_perturbed = self
+                .as_symbolic_context()
+                .mk_uninterpreted_function_is_true(*is_perturbed, &[]);
+            let colors_is_perturbed = self.unit_colors().copy(bdd_is_perturbed);
+            self.unit_colored_vertices()
+                .intersect_colors(&colors_is_perturbed)
+        } else {
+            self.mk_empty_colored_vertices()
+        }
+    }
+
+    /// Return a subset of colors for which the regulation from `regulator` to `target` is
+    /// not perturbed.
+    pub fn edge_not_perturbed(&self, regulator: VariableId, target: VariableId) -> GraphColors {
+        self.mk_unit_colors()
+            .minus(&self.fix_edge_perturbation(regulator, target).colors())
+    }
+
     /// Compute the subset of `target` to which a jump from `source` is possible using a perturbation.
     pub fn post_perturbation(
         &self,
@@ -203,29 +453,260 @@ impl PerturbationGraph {
         target: &GraphColoredVertices,
     ) -> GraphColoredVertices {
         /*
-           The algorithm is based on iteratively removing the "negative results", i.e. the states
-           which are not reachable by a perturbation. For this, we observe that a state is not
-           reachable via a perturbation if there is a variable that is not perturbed and yet
-           it is different from its value in source (since perturbation will not touch this value,
-           it will not change and hence has to be the same).
-
-           We thus specify a set of such unreachable states (per variable) and remove these states
-           from the `target` set, together with all colours. The state-colour pairs that remain
-           then represent the perturbations that can be applied to jump into target.
-
-           (Alternatively, we could compute a "positive result", i.e.
-               !is_perturbed[var] => source[var] = target[var]
-           however, that would need some more custom BDD fiddling and is therefore easier to
-           avoid at the moment)
+           A state is reachable via a perturbation if every variable either is not perturbed
+           and keeps its value from source, or is perturbed to one of its allowed values (since
+           perturbation will not touch the unperturbed values, they have to be the same).
+
+           We thus specify a set of such admissible states (per variable) and intersect them
+           with the `target` set. The state-colour pairs that remain then represent the
+           perturbations that can be applied to jump into target.
         */
         let mut result = target.clone();
         for v in self.variables() {
             let value_in_source = source.get(usize::from(v));
-            let mismatched_states = self.fix_variable(v, !value_in_source);
-            let v_not_perturbed = self.not_perturbed(v);
-            result = result.minus(&mismatched_states.intersect_colors(&v_not_perturbed));
+            let keeps_value = self
+                .fix_variable(v, value_in_source)
+                .intersect_colors(&self.not_perturbed(v));
+            result = result.intersect(&keeps_value.union(&self.fix_perturbation(v, None)));
         }
 
         result
     }
+
+    /// Compute the subset of `target` to which a jump from the `sources` set is possible using
+    /// a perturbation (in the respective color).
+    ///
+    /// With `SourceQuantifier::Exists`, the jump must be possible from at least one source state.
+    /// With `SourceQuantifier::ForAll`, the result only contains jumps whose perturbation leads
+    /// into `target` from every source state (of the respective color).
+    pub fn post_perturbation_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        target: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+    ) -> GraphColoredVertices {
+        /*
+           A perturbation can change the value of every perturbed variable, while the remaining
+           variables keep their value from the source state. Hence, for every variable, we
+           "forget" its value in the colors where it is perturbed.
+
+           For the universal variant, we also compute the jumps that end outside of `target`.
+           Every state which has the same values of perturbed variables as one of these jumps
+           is then removed, since the same perturbation fails for some source state.
+        */
+        let can_jump_to = self.forget_values(sources, true);
+        let good_jumps = target.intersect(&can_jump_to);
+        match quantifier {
+            SourceQuantifier::Exists => good_jumps,
+            SourceQuantifier::ForAll => {
+                let bad_jumps = can_jump_to.minus(target);
+                good_jumps.minus(&self.forget_values(&bad_jumps, false))
+            }
+        }
+    }
+
+    /// Existentially abstract the values of variables which are perturbed (if `perturbed` is
+    /// set) or not perturbed (otherwise) in the respective color.
+    ///
+    /// The values of perturbed variables are only abstracted within their allowed values, i.e.
+    /// the result of abstracting perturbed values is always a set of the perturbed graph.
+    pub(crate) fn forget_values(
+        &self,
+        set: &GraphColoredVertices,
+        perturbed: bool,
+    ) -> GraphColoredVertices {
+        let mut result = set.clone();
+        for v in self.variables() {
+            let is_perturbed = self
+                .mk_unit_colored_vertices()
+                .minus_colors(&self.not_perturbed(v));
+            let (forget, keep) = if perturbed {
+                (result.intersect(&is_perturbed), result.minus(&is_perturbed))
+            } else {
+                (result.minus(&is_perturbed), result.intersect(&is_perturbed))
+            };
+            let state_var = self.as_symbolic_context().get_state_variable(v);
+            let forgotten = forget.as_bdd().var_exists(state_var);
+            result = keep.union(&self.empty_colored_vertices().copy(forgotten));
+        }
+        if perturbed {
+            result.intersect(self.as_perturbed().unit_colored_vertices())
+        } else {
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ControlError;
+    use crate::perturbation::PerturbationGraph;
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
+    use biodivine_lib_param_bn::fixed_points::FixedPoints;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use biodivine_lib_param_bn::{BooleanNetwork, Monotonicity};
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn test_perturbed_unit_colors() {
+        // The perturbed function of `A` is not monotonous in `A`, so the default unit set
+        // of the perturbed network would not contain colors where `A` is perturbed.
+        let model = BooleanNetwork::try_from("A -| A\nA -> B\n$A: !A\n$B: A").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let perturbations = PerturbationGraph::with_restricted_variables(&model, &[a]);
+
+        assert_eq!(
+            perturbations.unit_colored_vertices(),
+            perturbations.as_perturbed().unit_colored_vertices()
+        );
+        assert_eq!(
+            2.0,
+            perturbations
+                .as_perturbed()
+                .unit_colors()
+                .approx_cardinality()
+        );
+
+        // With `A` perturbed to true, the network has a fixed point.
+        let perturbed = perturbations
+            .as_perturbed()
+            .unit_colored_vertices()
+            .intersect(&perturbations.fix_perturbation(a, Some(true)));
+        let fixed_points = FixedPoints::symbolic(perturbations.as_perturbed(), &perturbed);
+        assert_eq!(1.0, fixed_points.vertices().approx_cardinality());
+    }
+
+    #[test]
+    pub fn test_regulation_constraints() {
+        // Both regulations are observable and activating, so the only admissible update
+        // function of both `A` and `B` is `A`.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B").unwrap();
+        let model_colors = SymbolicAsyncGraph::new(&model)
+            .unwrap()
+            .unit_colors()
+            .approx_cardinality();
+        assert_eq!(1.0, model_colors);
+
+        // Perturbation parameters are unconstrained, the rest must follow the model (also
+        // when a variable is perturbed, since a perturbation can be released).
+        let perturbations = PerturbationGraph::new(&model);
+        assert_eq!(
+            model_colors * 4.0,
+            perturbations.unit_colors().approx_cardinality()
+        );
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        assert_eq!(
+            model_colors * 2.0,
+            perturbations.not_perturbed(a).approx_cardinality()
+        );
+        // In the perturbed graph, perturbing a variable cuts its regulations, so then any of
+        // its four update functions is allowed.
+        assert_eq!(
+            (model_colors + 4.0) * (model_colors + 4.0),
+            perturbations
+                .as_perturbed()
+                .unit_colors()
+                .approx_cardinality()
+        );
+
+        // Perturbing `B` cuts its regulation: `B` keeps its value regardless of `A`, even
+        // though the network has no `B -> B` regulation.
+        let original = perturbations.as_original().as_network().unwrap();
+        assert!(original.as_graph().find_regulation(b, b).is_none());
+        let perturbed = perturbations.as_perturbed().as_network().unwrap();
+        let self_loop = perturbed.as_graph().find_regulation(b, b).unwrap();
+        assert!(!self_loop.is_observable());
+        assert_eq!(None, self_loop.get_monotonicity());
+        let perturbed = perturbations
+            .mk_unit_colored_vertices()
+            .intersect(&perturbations.fix_perturbation(b, Some(false)));
+        let fixed_points = FixedPoints::symbolic(perturbations.as_perturbed(), &perturbed);
+        assert_eq!(
+            perturbations
+                .fix_variable(b, false)
+                .intersect(&fixed_points),
+            fixed_points
+        );
+        assert!(!fixed_points.is_empty());
+    }
+
+    #[test]
+    pub fn test_edge_perturbation_constraints() {
+        // Perturbing `A -> B` cuts the regulation, so the function of `B` no longer has to
+        // depend on `A` positively.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        let perturbations =
+            PerturbationGraph::try_with_edge_perturbations(&model, &[], &[(a, b, false)]).unwrap();
+        assert_eq!(2.0, perturbations.unit_colors().approx_cardinality());
+        let perturbed_colors = perturbations.as_perturbed().unit_colors();
+        assert_eq!(5.0, perturbed_colors.approx_cardinality());
+        let edge_perturbed = perturbed_colors.minus(&perturbations.edge_not_perturbed(a, b));
+        assert_eq!(4.0, edge_perturbed.approx_cardinality());
+    }
+
+    #[test]
+    pub fn test_unsatisfiable_regulation() {
+        // `A` is an observable regulator of `B`, but `B` is constant.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B\n$B: true").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        assert!(matches!(
+            PerturbationGraph::try_with_restricted_variables(&model, &[a]),
+            Err(ControlError::InvalidNetwork(_))
+        ));
+
+        // Once relaxed, the regulation is non-observable, but the constraints of `A -> A`
+        // still apply.
+        let (relaxed, relaxed_regulations) =
+            PerturbationGraph::relax_unsatisfiable_regulations(&model).unwrap();
+        assert_eq!(vec![(a, b)], relaxed_regulations);
+        let regulation = relaxed.as_graph().find_regulation(a, b).unwrap();
+        assert!(!regulation.is_observable());
+        assert_eq!(
+            Some(Monotonicity::Activation),
+            regulation.get_monotonicity()
+        );
+        let perturbations =
+            PerturbationGraph::try_with_restricted_variables(&relaxed, &[a]).unwrap();
+        // Out of the four functions of `A`, only the identity is allowed (in the perturbed
+        // graph, unless `A` is perturbed).
+        assert_eq!(1.0, perturbations.not_perturbed(a).approx_cardinality());
+        assert_eq!(2.0, perturbations.unit_colors().approx_cardinality());
+        assert_eq!(
+            5.0,
+            perturbations
+                .as_perturbed()
+                .unit_colors()
+                .approx_cardinality()
+        );
+
+        // The over-expressed `v_EGFR` is constant, which contradicts its observable regulations.
+        let model = std::fs::read_to_string(
+            "models_phenotype/[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__egfr_oe_witness_0000.aeon",
+        )
+        .unwrap();
+        let model = BooleanNetwork::try_from(model.as_str()).unwrap();
+        let egfr = model.as_graph().find_variable("v_EGFR").unwrap();
+        let vars = model.variables().collect::<Vec<_>>();
+        assert!(PerturbationGraph::try_with_restricted_variables(&model, &vars).is_err());
+        let (relaxed, relaxed_regulations) =
+            PerturbationGraph::relax_unsatisfiable_regulations(&model).unwrap();
+        assert!(relaxed_regulations
+            .iter()
+            .any(|(_, target)| *target == egfr));
+        let perturbations =
+            PerturbationGraph::try_with_restricted_variables(&relaxed, &vars).unwrap();
+        assert!(!perturbations.not_perturbed(egfr).is_empty());
+
+        // Each regulation can be satisfied on its own, but `f` cannot be both activating
+        // (in `B`) and inhibiting (in `C`).
+        let model = BooleanNetwork::try_from("A -> B\nA -> C\n$B: f(A)\n$C: !f(A)").unwrap();
+        assert!(matches!(
+            PerturbationGraph::try_with_perturbable_names(&model, &["A"]),
+            Err(ControlError::InvalidNetwork(_))
+        ));
+    }
 }
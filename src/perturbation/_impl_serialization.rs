@@ -0,0 +1,139 @@
+use crate::error::ControlError;
+use crate::perturbation::PerturbationGraph;
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_param_bn::BooleanNetwork;
+use serde_json::{json, Map, Value};
+use std::convert::TryFrom;
+use std::path::Path;
+
+impl PerturbationGraph {
+    /// Metadata needed to rebuild this graph using `PerturbationGraph::try_from_json`: the model
+    /// (in `.aeon` format), the perturbable variables with their allowed values and the
+    /// perturbable regulations.
+    pub(crate) fn to_json(&self) -> Value {
+        let mut perturbable = Map::new();
+        for var in self.variables() {
+            if self.get_perturbation_parameter(var).is_some() {
+                let value = match self.get_perturbation_value(var) {
+                    Some(value) => Value::Bool(value),
+                    None => Value::Null,
+                };
+                perturbable.insert(self.network.get_variable_name(var).clone(), value);
+            }
+        }
+        let edges = self
+            .edge_perturbations()
+            .into_iter()
+            .map(|(regulator, target)| {
+                json!({
+                    "regulator": self.network.get_variable_name(regulator),
+                    "target": self.network.get_variable_name(target),
+                    "value": self.get_edge_perturbation_value(regulator, target),
+                })
+            })
+            .collect::<Vec<_>>();
+        let variables = self
+            .variables()
+            .map(|var| self.network.get_variable_name(var).clone())
+            .collect::<Vec<_>>();
+        json!({
+            "model": self.network.to_string(),
+            "variables": variables,
+            "perturbable": perturbable,
+            "perturbable_edges": edges,
+        })
+    }
+
+    /// Rebuild a graph from the metadata created by `PerturbationGraph::to_json`.
+    pub(crate) fn try_from_json(value: &Value) -> Result<PerturbationGraph, ControlError> {
+        let model = value["model"]
+            .as_str()
+            .ok_or_else(|| invalid("Missing model."))?;
+        let network = BooleanNetwork::try_from(model).map_err(ControlError::InvalidNetwork)?;
+
+        // The symbolic encoding depends on the order of variables, so it must not change.
+        let variables = network
+            .variables()
+            .map(|var| Value::String(network.get_variable_name(var).clone()))
+            .collect::<Vec<_>>();
+        if value["variables"].as_array() != Some(&variables) {
+            return Err(invalid("The model variables do not match."));
+        }
+
+        let perturbable = value["perturbable"]
+            .as_object()
+            .ok_or_else(|| invalid("Missing perturbable variables."))?;
+        let mut perturb = Vec::new();
+        for (name, value) in perturbable {
+            let var = network
+                .as_graph()
+                .find_variable(name)
+                .ok_or_else(|| ControlError::UnknownVariable(name.clone()))?;
+            let value = match value {
+                Value::Null => None,
+                Value::Bool(value) => Some(*value),
+                _ => {
+                    return Err(invalid(&format!(
+                        "Invalid perturbation value of {:?}.",
+                        name
+                    )))
+                }
+            };
+            perturb.push((var, value));
+        }
+
+        let perturbable_edges = value["perturbable_edges"]
+            .as_array()
+            .ok_or_else(|| invalid("Missing perturbable regulations."))?;
+        let mut perturb_edges = Vec::new();
+        for edge in perturbable_edges {
+            let find_variable = |key: &str| {
+                let name = edge[key]
+                    .as_str()
+                    .ok_or_else(|| invalid("Invalid perturbable regulation."))?;
+                network
+                    .as_graph()
+                    .find_variable(name)
+                    .ok_or_else(|| ControlError::UnknownVariable(name.to_string()))
+            };
+            let value = edge["value"]
+                .as_bool()
+                .ok_or_else(|| invalid("Invalid perturbable regulation."))?;
+            perturb_edges.push((find_variable("regulator")?, find_variable("target")?, value));
+        }
+        PerturbationGraph::try_with_edge_perturbations(&network, &perturb, &perturb_edges)
+    }
+
+    /// Read a `Bdd` created by `Bdd::to_string` and check that it uses the symbolic variables
+    /// of this graph.
+    pub(crate) fn try_bdd_from_json(&self, value: &Value) -> Result<Bdd, ControlError> {
+        let bdd = value.as_str().ok_or_else(|| invalid("Missing BDD."))?;
+        let bdd = Bdd::read_as_string(&mut bdd.as_bytes()).map_err(|e| invalid(&e))?;
+        if bdd.num_vars() != self.as_symbolic_context().bdd_variable_set().num_vars() {
+            return Err(invalid("The BDD does not match the model."));
+        }
+        Ok(bdd)
+    }
+}
+
+pub(crate) fn write_json<P: AsRef<Path>>(path: P, value: &Value) -> Result<(), ControlError> {
+    std::fs::write(path, value.to_string()).map_err(|e| ControlError::Io(e.to_string()))
+}
+
+pub(crate) fn read_json<P: AsRef<Path>>(path: P) -> Result<Value, ControlError> {
+    let data = std::fs::read_to_string(path).map_err(|e| ControlError::Io(e.to_string()))?;
+    serde_json::from_str(data.as_str()).map_err(|e| invalid(&e.to_string()))
+}
+
+/// Check that `value` is a serialized object of the given `kind`.
+pub(crate) fn check_kind(value: &Value, kind: &str) -> Result<(), ControlError> {
+    if value["kind"].as_str() == Some(kind) {
+        Ok(())
+    } else {
+        Err(invalid(&format!("Expected {:?}.", kind)))
+    }
+}
+
+pub(crate) fn invalid(message: &str) -> ControlError {
+    ControlError::InvalidControlMap(message.to_string())
+}
@@ -1,14 +1,15 @@
 use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
-use biodivine_lib_param_bn::{ParameterId, VariableId};
+use biodivine_lib_param_bn::{BooleanNetwork, ParameterId, VariableId};
 use std::collections::HashMap;
 
 /// Procedures for transforming Boolean networks so that they conform to our encoding.
 ///
 /// In particular, here we have functions for normalizing the network before it is used in
-/// a perturbed graph and then creating the "original" and "perturbed" network from the normalized
-/// result.
+/// a perturbed graph and then creating the "original" network and the "perturbed" graph from
+/// the normalized result.
 mod _algo_network_transformations;
 mod _impl_perturbation_graph;
+pub(crate) mod _impl_serialization;
 
 /// Perturbation graph allows representing the *original* `SymbolicAsyncGraph` as well as
 /// the async graph with perturbations encoded in parameters. Currently, we are "hacking"
@@ -22,10 +23,27 @@ mod _impl_perturbation_graph;
 /// for computing pre/post.
 #[derive(Clone)]
 pub struct PerturbationGraph {
+    /// The network from which the graph was created (before normalization).
+    network: BooleanNetwork,
     /// "Normal" unperturbed graph, but with the same encoding as the perturbed graph.
     original_graph: SymbolicAsyncGraph,
     /// Perturbed graph where each edge is also labelled with perturbations that enable it.
     perturbed_graph: SymbolicAsyncGraph,
     /// Obtain parameters that decide whether a specific variable is perturbed.
     perturbation_parameters: HashMap<VariableId, ParameterId>,
+    /// Perturbable variables which can only be perturbed to the given value (e.g. knockout-only).
+    perturbation_values: HashMap<VariableId, bool>,
+    /// Parameters that decide whether a regulation (regulator, target) is perturbed.
+    edge_perturbation_parameters: HashMap<(VariableId, VariableId), ParameterId>,
+    /// The values to which the regulators of perturbable regulations are frozen.
+    edge_perturbation_values: HashMap<(VariableId, VariableId), bool>,
+}
+
+/// Decides how a perturbation applied to a set of source states is evaluated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceQuantifier {
+    /// The perturbation must work for at least one source state.
+    Exists,
+    /// The perturbation must work regardless of which source state it is applied in.
+    ForAll,
 }
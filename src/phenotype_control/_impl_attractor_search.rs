@@ -0,0 +1,286 @@
+use crate::aeon::attractors;
+use crate::error::ControlError;
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::{
+    AttractorBackend, AttractorSearch, ComplexAttractorSearch, PhenotypeMembership,
+    MAX_AT_LEAST_COLORS,
+};
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::fixed_points::FixedPoints;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphVertices, SymbolicAsyncGraph,
+};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+impl FromStr for AttractorSearch {
+    type Err = ControlError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sinks" => Ok(AttractorSearch::Sinks),
+            "complex" => Ok(AttractorSearch::Complex),
+            "heuristic" => Ok(AttractorSearch::Heuristic),
+            _ => Err(ControlError::UnknownAttractorSearch(value.to_string())),
+        }
+    }
+}
+
+impl Display for AttractorSearch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttractorSearch::Sinks => write!(f, "sinks"),
+            AttractorSearch::Complex => write!(f, "complex"),
+            AttractorSearch::Heuristic => write!(f, "heuristic"),
+        }
+    }
+}
+
+impl AttractorBackend for AttractorSearch {
+    fn phenotype_violating_attractors(
+        &self,
+        context: &PerturbationGraph,
+        graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices {
+        match self {
+            AttractorSearch::Sinks => {
+                if cfg!(feature = "print_progress") {
+                    eprintln!("------- Using fixed points implementation");
+                }
+                // Fixed points are single states, hence it is sufficient to only search
+                // outside of the phenotype.
+                let phenotype_violating_space = universe.minus_vertices(phenotype);
+                if cfg!(feature = "print_progress") {
+                    eprintln!(
+                        "space to explore attractors {}",
+                        phenotype_violating_space.approx_cardinality()
+                    );
+                }
+                FixedPoints::symbolic(graph, &phenotype_violating_space)
+            }
+            AttractorSearch::Complex => ComplexAttractorSearch::new(PhenotypeMembership::StaysIn)
+                .phenotype_violating_attractors(context, graph, universe, phenotype),
+            AttractorSearch::Heuristic => HeuristicAttractorSearch
+                .phenotype_violating_attractors(context, graph, universe, phenotype),
+        }
+    }
+}
+
+/// Selects between `AttractorSearch::Sinks` and `AttractorSearch::Complex` based on the
+/// attractors of the unperturbed network.
+///
+/// If all attractors of the unperturbed network are fixed points, we assume the perturbed
+/// network only has fixed points as well. Note that this is only a heuristic: a perturbation
+/// can in theory introduce new complex attractors.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeuristicAttractorSearch;
+
+impl HeuristicAttractorSearch {
+    /// Decide which attractor search should be used for the given perturbation graph.
+    pub fn select(&self, context: &PerturbationGraph) -> AttractorSearch {
+        let unperturbed_attractors = attractors::compute(context.as_original());
+        let mut unperturbed_attractors_all = context.as_original().mk_empty_colored_vertices();
+        for ua in unperturbed_attractors {
+            unperturbed_attractors_all = unperturbed_attractors_all.union(&ua);
+        }
+        let unperturbed_attractors_fps = FixedPoints::symbolic(
+            context.as_original(),
+            context.as_original().unit_colored_vertices(),
+        );
+
+        if cfg!(feature = "print_progress") {
+            eprintln!(
+                "FPs {:?}",
+                unperturbed_attractors_fps.vertices().approx_cardinality()
+            );
+            eprintln!(
+                "ALL {:?}",
+                unperturbed_attractors_all.vertices().approx_cardinality()
+            );
+        }
+        if unperturbed_attractors_all
+            .vertices()
+            .is_subset(&unperturbed_attractors_fps.vertices())
+        {
+            AttractorSearch::Sinks
+        } else {
+            AttractorSearch::Complex
+        }
+    }
+}
+
+impl AttractorBackend for HeuristicAttractorSearch {
+    fn phenotype_violating_attractors(
+        &self,
+        context: &PerturbationGraph,
+        graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices {
+        self.select(context)
+            .phenotype_violating_attractors(context, graph, universe, phenotype)
+    }
+}
+
+impl ComplexAttractorSearch {
+    /// Create a complex attractor search with the given `membership`.
+    ///
+    /// Returns an error if the fraction of `PhenotypeMembership::AtLeast` is not a number
+    /// between `0.0` and `1.0`.
+    pub fn try_new(
+        membership: PhenotypeMembership,
+    ) -> Result<ComplexAttractorSearch, ControlError> {
+        if let PhenotypeMembership::AtLeast(fraction) = membership {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(ControlError::InvalidPhenotypeMembership(format!(
+                    "Fraction {} is not between 0.0 and 1.0",
+                    fraction
+                )));
+            }
+        }
+        Ok(ComplexAttractorSearch { membership })
+    }
+
+    /// Panicking variant of `try_new`.
+    pub fn new(membership: PhenotypeMembership) -> ComplexAttractorSearch {
+        Self::try_new(membership).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn membership(&self) -> PhenotypeMembership {
+        self.membership
+    }
+}
+
+impl AttractorBackend for ComplexAttractorSearch {
+    fn phenotype_violating_attractors(
+        &self,
+        _context: &PerturbationGraph,
+        graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices {
+        if cfg!(feature = "print_progress") {
+            eprintln!("------- Using all attractors implementation");
+        }
+        let complex_attractors = attractors::compute_restricted(graph, universe.clone());
+        let mut phenotype_violating_attractors = graph.mk_empty_colored_vertices();
+        for attractor in &complex_attractors {
+            let violating_attractor =
+                violating_colors(graph, attractor, phenotype, self.membership);
+            phenotype_violating_attractors =
+                phenotype_violating_attractors.union(&violating_attractor);
+        }
+        phenotype_violating_attractors
+    }
+}
+
+/// An attractor backend which uses a user-supplied list of attractors instead of computing them.
+///
+/// Each item of the list should be a single attractor (for every color in which it appears),
+/// i.e. the same format that is returned by `aeon::attractors::compute`. The attractors must
+/// be valid for the graph in which the phenotype control is computed (usually the perturbed
+/// graph).
+#[derive(Clone)]
+pub struct PrecomputedAttractors {
+    attractors: Vec<GraphColoredVertices>,
+}
+
+impl PrecomputedAttractors {
+    pub fn new(attractors: Vec<GraphColoredVertices>) -> PrecomputedAttractors {
+        PrecomputedAttractors { attractors }
+    }
+
+    pub fn attractors(&self) -> &[GraphColoredVertices] {
+        &self.attractors
+    }
+}
+
+impl AttractorBackend for PrecomputedAttractors {
+    fn phenotype_violating_attractors(
+        &self,
+        _context: &PerturbationGraph,
+        graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices {
+        let restricted = self
+            .attractors
+            .iter()
+            .map(|it| it.intersect(universe))
+            .collect::<Vec<_>>();
+        violating_attractor_states(restricted.iter(), phenotype, graph)
+    }
+}
+
+/// Collect the states of all `attractors` which have at least one state outside of
+/// the `phenotype` (in the respective color).
+fn violating_attractor_states<'a, I>(
+    attractors: I,
+    phenotype: &GraphVertices,
+    graph: &SymbolicAsyncGraph,
+) -> GraphColoredVertices
+where
+    I: Iterator<Item = &'a GraphColoredVertices>,
+{
+    let mut phenotype_violating_attractors = graph.mk_empty_colored_vertices();
+    for attractor in attractors {
+        let violating_attractor =
+            violating_colors(graph, attractor, phenotype, PhenotypeMembership::StaysIn);
+        phenotype_violating_attractors = phenotype_violating_attractors.union(&violating_attractor);
+    }
+    phenotype_violating_attractors
+}
+
+/// Restrict a single `attractor` to the colors in which it does not respect the `phenotype`.
+fn violating_colors(
+    graph: &SymbolicAsyncGraph,
+    attractor: &GraphColoredVertices,
+    phenotype: &GraphVertices,
+    membership: PhenotypeMembership,
+) -> GraphColoredVertices {
+    match membership {
+        PhenotypeMembership::StaysIn => {
+            let colors_with_states_outside_phenotype = attractor.minus_vertices(phenotype).colors();
+            attractor.intersect_colors(&colors_with_states_outside_phenotype)
+        }
+        PhenotypeMembership::Visits => {
+            let colors_with_states_in_phenotype = attractor.intersect_vertices(phenotype).colors();
+            attractor.minus_colors(&colors_with_states_in_phenotype)
+        }
+        PhenotypeMembership::AtLeast(fraction) => {
+            /*
+               The attractor can have different states in different colors. We thus split its
+               colors into groups where the attractor has the same states, and compute the
+               fraction of phenotype states for each group separately. The number of groups
+               is only bounded by the number of colors, hence the size check.
+            */
+            let color_count = attractor.colors().approx_cardinality();
+            if color_count > MAX_AT_LEAST_COLORS {
+                panic!(
+                    "Attractor with {} colors is too large for phenotype membership `at least` (limit {}).",
+                    color_count, MAX_AT_LEAST_COLORS
+                );
+            }
+            let mut violating = graph.mk_empty_colored_vertices();
+            let mut remaining = attractor.clone();
+            while !remaining.is_empty() {
+                let color = remaining.colors().pick_singleton();
+                let states = remaining.intersect_colors(&color).vertices();
+                let states_in_all_colors = attractor.copy(states.as_bdd().clone());
+                let differs = attractor
+                    .minus(&states_in_all_colors)
+                    .union(&states_in_all_colors.minus(attractor))
+                    .colors();
+                let group = attractor.colors().minus(&differs);
+                let inside = states.intersect(phenotype).approx_cardinality();
+                if inside < fraction * states.approx_cardinality() {
+                    violating = violating.union(&attractor.intersect_colors(&group));
+                }
+                remaining = remaining.minus_colors(&group);
+            }
+            violating
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::ControlError;
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::_symbolic_utils::{mk_bdd_of_cost, mk_bdd_up_to_cost, CostVariable};
+use crate::phenotype_control::PerturbationCosts;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::VariableId;
+use std::collections::HashMap;
+
+impl PerturbationCosts {
+    /// Create costs where every perturbation (of a variable or a regulation) costs `1`.
+    pub fn new() -> PerturbationCosts {
+        PerturbationCosts::default()
+    }
+
+    /// Set the cost of perturbing `variable` to `value`.
+    pub fn set_cost(&mut self, variable: VariableId, value: bool, cost: usize) {
+        self.costs.insert((variable, value), cost);
+    }
+
+    /// The same as `set_cost`, but sets the cost for both values of `variable`.
+    pub fn set_variable_cost(&mut self, variable: VariableId, cost: usize) {
+        self.set_cost(variable, false, cost);
+        self.set_cost(variable, true, cost);
+    }
+
+    /// Set the cost of perturbing the regulation from `regulator` to `target`.
+    pub fn set_edge_cost(&mut self, regulator: VariableId, target: VariableId, cost: usize) {
+        self.edge_costs.insert((regulator, target), cost);
+    }
+
+    /// The cost of perturbing `variable` to `value`.
+    pub fn cost(&self, variable: VariableId, value: bool) -> usize {
+        self.costs.get(&(variable, value)).cloned().unwrap_or(1)
+    }
+
+    /// The cost of perturbing the regulation from `regulator` to `target`.
+    pub fn edge_cost(&self, regulator: VariableId, target: VariableId) -> usize {
+        self.edge_costs
+            .get(&(regulator, target))
+            .cloned()
+            .unwrap_or(1)
+    }
+
+    /// The total cost of a perturbation of variables.
+    pub fn perturbation_cost(&self, perturbation: &HashMap<VariableId, bool>) -> usize {
+        perturbation
+            .iter()
+            .map(|(variable, value)| self.cost(*variable, *value))
+            .sum()
+    }
+
+    /// The total cost of a perturbation of variables and of the regulations `edges`.
+    pub fn total_cost(
+        &self,
+        perturbation: &HashMap<VariableId, bool>,
+        edges: &[(VariableId, VariableId)],
+    ) -> usize {
+        let edges_cost = edges
+            .iter()
+            .map(|(regulator, target)| self.edge_cost(*regulator, *target))
+            .sum::<usize>();
+        self.perturbation_cost(perturbation) + edges_cost
+    }
+}
+
+impl PerturbationGraph {
+    /// Build the set of states and colors which perturb the given `perturbation_variables`
+    /// with total cost at most `budget`. Perturbation parameters of other variables and of
+    /// regulations are not restricted.
+    ///
+    /// Unlike `try_mk_admissible_perturbations`, the result also depends on the (perturbed)
+    /// state, because the cost of a perturbation depends on its value.
+    pub fn try_mk_admissible_perturbations_by_cost(
+        &self,
+        perturbation_variables: &[VariableId],
+        costs: &PerturbationCosts,
+        budget: usize,
+    ) -> Result<GraphColoredVertices, ControlError> {
+        if perturbation_variables.is_empty() {
+            return Err(ControlError::NoPerturbableVariables);
+        }
+        let variables = self.try_mk_cost_variables(perturbation_variables, costs)?;
+        let bdd_vars = self.as_symbolic_context().bdd_variable_set();
+        let bdd = mk_bdd_up_to_cost(bdd_vars, &variables, budget);
+        let unit = self.mk_unit_colored_vertices();
+        Ok(unit.intersect(&unit.copy(bdd)))
+    }
+
+    /// The same as `try_mk_admissible_perturbations_by_cost`, but only perturbations with
+    /// total cost exactly `cost` are admissible.
+    pub fn try_mk_perturbations_of_cost(
+        &self,
+        perturbation_variables: &[VariableId],
+        costs: &PerturbationCosts,
+        cost: usize,
+    ) -> Result<GraphColoredVertices, ControlError> {
+        if perturbation_variables.is_empty() {
+            return Err(ControlError::NoPerturbableVariables);
+        }
+        let variables = self.try_mk_cost_variables(perturbation_variables, costs)?;
+        let bdd_vars = self.as_symbolic_context().bdd_variable_set();
+        let bdd = mk_bdd_of_cost(bdd_vars, &variables, cost);
+        let unit = self.mk_unit_colored_vertices();
+        Ok(unit.intersect(&unit.copy(bdd)))
+    }
+
+    /// Build the set of states and colors where the perturbation of all perturbable variables
+    /// and regulations has total cost exactly `cost`.
+    pub(crate) fn mk_all_perturbations_of_cost(
+        &self,
+        costs: &PerturbationCosts,
+        cost: usize,
+    ) -> GraphColoredVertices {
+        let perturbable = self
+            .variables()
+            .filter(|it| self.get_perturbation_parameter(*it).is_some())
+            .collect::<Vec<_>>();
+        let mut variables = self.try_mk_cost_variables(&perturbable, costs).unwrap();
+        for ((regulator, target), p_var) in self.edge_perturbation_vars() {
+            let edge_cost = costs.edge_cost(regulator, target);
+            variables.push(CostVariable {
+                parameter: p_var,
+                state: None,
+                cost_false: edge_cost,
+                cost_true: edge_cost,
+            });
+        }
+        let bdd_vars = self.as_symbolic_context().bdd_variable_set();
+        let bdd = mk_bdd_of_cost(bdd_vars, &variables, cost);
+        let unit = self.mk_unit_colored_vertices();
+        unit.intersect(&unit.copy(bdd))
+    }
+
+    fn try_mk_cost_variables(
+        &self,
+        perturbation_variables: &[VariableId],
+        costs: &PerturbationCosts,
+    ) -> Result<Vec<CostVariable>, ControlError> {
+        let symbolic_context = self.as_symbolic_context();
+        let mut result = Vec::new();
+        for var in perturbation_variables.iter().copied() {
+            if let Some(p) = self.get_perturbation_parameter(var) {
+                let table = symbolic_context.get_explicit_function_table(p);
+                result.push(CostVariable {
+                    parameter: table.symbolic_variables()[0],
+                    state: Some(symbolic_context.get_state_variable(var)),
+                    cost_false: costs.cost(var, false),
+                    cost_true: costs.cost(var, true),
+                });
+            } else if var.to_index() < self.as_original().num_vars() {
+                let name = self.as_original().get_variable_name(var);
+                return Err(ControlError::NotPerturbable(name));
+            } else {
+                return Err(ControlError::InvalidVariableId(var));
+            }
+        }
+        Ok(result)
+    }
+}
@@ -1,5 +1,11 @@
-use crate::phenotype_control::PhenotypeControlMap;
+use crate::control::ControlMode;
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::_simplified_algorithm::enumerate_perturbations;
+use crate::phenotype_control::{
+    BoundedPerturbation, PerturbationCosts, PhenotypeControlMap, PhenotypeControlSemantics,
+};
 use biodivine_lib_bdd::Bdd;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, GraphColors};
 use biodivine_lib_param_bn::VariableId;
 use std::collections::HashMap;
@@ -14,6 +20,56 @@ impl PhenotypeControlMap {
         &self.perturbation_set
     }
 
+    /// The perturbation graph for which this map was computed.
+    pub fn as_perturbation_graph(&self) -> &PerturbationGraph {
+        &self.context
+    }
+
+    /// The kind of control under which this map was computed.
+    pub fn mode(&self) -> ControlMode {
+        self.mode
+    }
+
+    /// The semantics under which this map was computed.
+    pub fn semantics(&self) -> &PhenotypeControlSemantics {
+        &self.semantics
+    }
+
+    /// List all perturbations with total cost up to `budget` (inclusive) which work for at
+    /// least one color, cheapest first.
+    pub fn cheapest_perturbations(
+        &self,
+        costs: &PerturbationCosts,
+        budget: usize,
+    ) -> Vec<BoundedPerturbation> {
+        // A perturbation works if it works for all values of unperturbed variables.
+        let symbolic_context = self.context.as_symbolic_context();
+        let mut working = self.perturbation_set.as_bdd().clone();
+        for v in self.context.variables() {
+            let state_var = symbolic_context.get_state_variable(v);
+            if let Some(parameter) = self.context.get_perturbation_parameter(v) {
+                let p_var = symbolic_context
+                    .get_explicit_function_table(parameter)
+                    .symbolic_variables()[0];
+                let is_perturbed = working.var_select(p_var, true);
+                let is_not_perturbed = working.var_select(p_var, false).var_for_all(state_var);
+                working = is_perturbed.or(&is_not_perturbed);
+            } else {
+                working = working.var_for_all(state_var);
+            }
+        }
+        let working = self.context.empty_colored_vertices().copy(working);
+
+        let mut result = Vec::new();
+        for cost in 0..(budget + 1) {
+            let of_cost = self.context.mk_all_perturbations_of_cost(costs, cost);
+            let mut perturbations =
+                enumerate_perturbations(&self.context, &working.intersect(&of_cost), costs);
+            result.append(&mut perturbations);
+        }
+        result
+    }
+
     pub fn perturbation_working_colors(&self, perturbation: &HashMap<String, bool>) -> GraphColors {
         let mut perturbation_bdd = self.perturbation_set.as_bdd().clone();
         // Obtain BDD having given variables perturbed to the specified value and remaining variables having unperturbed
@@ -41,6 +97,11 @@ impl PhenotypeControlMap {
                 perturbation_bdd = perturbation_bdd.and(&self.context.not_perturbed(v).into_bdd());
             }
         }
+        // The perturbation does not perturb any regulation.
+        for (regulator, target) in self.context.edge_perturbations() {
+            let not_perturbed = self.context.edge_not_perturbed(regulator, target);
+            perturbation_bdd = perturbation_bdd.and(&not_perturbed.into_bdd());
+        }
 
         // let mut file = File::create("./dot_result.txt").unwrap();
         // file.write_all(perturbation_bdd.to_dot_string(self.context.as_symbolic_context().bdd_variable_set(), true).as_bytes()).unwrap();
@@ -81,7 +142,9 @@ impl PhenotypeControlMap {
         let mut minimal_found = false;
         for i in 1..(max_size + 1) {
             let now = Instant::now();
-            println!("Exploring perturbations of size {:?}", i);
+            if verbose {
+                eprintln!("Exploring perturbations of size {:?}", i);
+            }
             let mut controls = self.rec_ceiled_size_perturbation_working_colors(
                 i,
                 min_cardinality,
@@ -90,21 +153,25 @@ impl PhenotypeControlMap {
                 HashMap::new(),
                 verbose,
             );
-            println!(
-                "Perturbations working for at least {:?} colors : {:?}",
-                min_cardinality,
-                controls.len()
-            );
-            let duration = now.elapsed();
-            println!(
-                "Exploring perturbations of size {:?} took {:?}",
-                i, duration
-            );
+            if verbose {
+                eprintln!(
+                    "Perturbations working for at least {:?} colors : {:?}",
+                    min_cardinality,
+                    controls.len()
+                );
+                eprintln!(
+                    "Exploring perturbations of size {:?} took {:?}",
+                    i,
+                    now.elapsed()
+                );
+            }
 
             perturbations.append(&mut controls);
 
             if !perturbations.is_empty() && !minimal_found {
-                println!("Minaml perturbations: {:?}", perturbations);
+                if verbose {
+                    eprintln!("Minaml perturbations: {:?}", perturbations);
+                }
                 minimal_found = true;
                 if stop_early {
                     return perturbations;
@@ -156,7 +223,7 @@ impl PhenotypeControlMap {
             return if gc.approx_cardinality() >= min_cardinality {
                 if verbose {
                     // println!("{:?}: {:?}", current_perturbation, gc.to_dot_string(self.context.as_symbolic_context()));
-                    println!("{:?}: ", current_perturbation);
+                    eprintln!("{:?}: ", current_perturbation);
                 }
                 vec![current_perturbation]
             } else {
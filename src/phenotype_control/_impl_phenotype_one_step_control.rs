@@ -0,0 +1,132 @@
+use crate::control::{ControlMap, ControlMode};
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
+use crate::phenotype_control::{AttractorBackend, AttractorSearch};
+use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices,
+};
+use std::sync::OnceLock;
+
+impl PerturbationGraph {
+    /// Compute one-step phenotype control map. That is, perturbations which are applied in
+    /// the `source` state for a single time step, after which the unperturbed network only
+    /// reaches attractors that lie in the `phenotype`.
+    ///
+    /// The result is a source-target `ControlMap`: its vertices are the states into which
+    /// the perturbation jumps.
+    pub fn phenotype_one_step_control(
+        &self,
+        source: &ArrayBitVector,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_search: AttractorSearch,
+    ) -> ControlMap {
+        self.phenotype_one_step_control_from_set(
+            &self.vertex(source),
+            // For a single source state, both quantifiers are equivalent.
+            SourceQuantifier::Exists,
+            phenotype,
+            admissible_perturbations,
+            &attractor_search,
+        )
+    }
+
+    /// The same as `phenotype_one_step_control`, but the perturbation is applied in a colored
+    /// set of `sources` states, and the phenotype-violating attractors are computed using
+    /// the given `attractor_backend`. The `quantifier` decides whether the perturbation must
+    /// work from every source state, or from at least one.
+    pub fn phenotype_one_step_control_from_set(
+        &self,
+        sources: &GraphColoredVertices,
+        quantifier: SourceQuantifier,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_backend: &dyn AttractorBackend,
+    ) -> ControlMap {
+        /*
+           Same as in source-target one-step control, we have to jump into the strong basin of
+           the target, which in this case consists of all phenotype-respecting attractors.
+        */
+        let strong_basin = self.phenotype_strong_basin_with(
+            phenotype,
+            &admissible_perturbations,
+            attractor_backend,
+        );
+        let can_jump_to = self.post_perturbation_from_set(sources, &strong_basin, quantifier);
+        ControlMap {
+            perturbation_set: can_jump_to,
+            context: self.clone(),
+            mode: ControlMode::OneStep,
+            colors: OnceLock::from(admissible_perturbations.clone()),
+            robustness_target: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::perturbation::{PerturbationGraph, SourceQuantifier};
+    use crate::phenotype_control::AttractorSearch;
+    use crate::tests::myeloid_fixtures::{
+        attractor_states, erythrocyte_phenotype, myeloid_witness,
+    };
+    use biodivine_lib_param_bn::biodivine_std::bitvector::BitVector;
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
+
+    #[test]
+    pub fn test_one_step_myeloid() {
+        let model = myeloid_witness();
+        let perturbations = PerturbationGraph::new(&model);
+        let eklf = model.as_graph().find_variable("EKLF").unwrap();
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+
+        // Use one attractor outside of the phenotype as source and one inside as target.
+        let states = attractor_states(&perturbations);
+        let source = states.iter().find(|it| !it.get(eklf.to_index())).unwrap();
+        let target = states.iter().find(|it| it.get(eklf.to_index())).unwrap();
+
+        let control = perturbations.phenotype_one_step_control(
+            source,
+            erythrocyte_phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Sinks,
+        );
+
+        // Jumping directly into the erythrocyte attractor works.
+        let mut trivial_control = control.clone();
+        for v in perturbations.variables() {
+            trivial_control.require_perturbation(v, Some(target.get(v.to_index())));
+        }
+        assert_eq!(1.0, trivial_control.as_bdd().cardinality());
+
+        // Doing nothing does not work.
+        let mut no_control = control.clone();
+        for v in perturbations.variables() {
+            no_control.exclude_perturbation(v, None);
+        }
+        assert!(no_control.as_colored_vertices().is_empty());
+
+        // Set-based jumps agree with jumps from a single state.
+        let unit = perturbations.mk_unit_colored_vertices();
+        assert_eq!(
+            perturbations.post_perturbation(source, &unit).as_bdd(),
+            perturbations
+                .post_perturbation_from_set(
+                    &perturbations.vertex(source),
+                    &unit,
+                    SourceQuantifier::Exists
+                )
+                .as_bdd()
+        );
+
+        // With a singleton source set, the result is the same.
+        let from_set = perturbations.phenotype_one_step_control_from_set(
+            &perturbations.vertex(source),
+            SourceQuantifier::ForAll,
+            erythrocyte_phenotype,
+            perturbations.mk_unit_colors(),
+            &AttractorSearch::Sinks,
+        );
+        assert_eq!(control.as_bdd(), from_set.as_bdd());
+    }
+}
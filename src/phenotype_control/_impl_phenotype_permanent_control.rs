@@ -1,195 +1,328 @@
-use crate::aeon::attractors;
-use crate::perturbation::PerturbationGraph;
-use crate::phenotype_control::PhenotypeControlMap;
+use crate::control::ControlMode;
+use crate::error::ControlError;
+use crate::perturbation::{PerturbationGraph, SourceQuantifier};
 use crate::phenotype_control::_symbolic_utils::mk_bdd_up_to_bound;
+use crate::phenotype_control::{
+    AttractorBackend, AttractorSearch, PerturbationCosts, PhenotypeControlMap,
+    PhenotypeControlSemantics,
+};
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::fixed_points::FixedPoints;
 use biodivine_lib_param_bn::symbolic_async_graph::reachability::Reachability;
-use biodivine_lib_param_bn::symbolic_async_graph::{GraphColors, GraphVertices};
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices,
+};
 use biodivine_lib_param_bn::VariableId;
 use chrono::Local;
 use std::time::Instant;
 
 impl PerturbationGraph {
+    /// Panicking variant of `try_ceiled_phenotype_permanent_control`.
     pub fn ceiled_phenotype_permanent_control(
         &self,
         phenotype: GraphVertices,
         max_size: usize,
         perturbation_variables: Vec<VariableId>,
-        attractor_search_method: &str,
+        attractor_search: AttractorSearch,
     ) -> PhenotypeControlMap {
-        assert!(!perturbation_variables.is_empty());
+        self.try_ceiled_phenotype_permanent_control(
+            phenotype,
+            max_size,
+            perturbation_variables,
+            attractor_search,
+        )
+        .unwrap_or_else(|e| panic!("{}", e))
+    }
 
+    /// Permanent phenotype control using perturbations of at most `max_size` of the
+    /// `perturbation_variables`. Returns an error when `perturbation_variables` is empty or
+    /// contains a variable that cannot be perturbed.
+    pub fn try_ceiled_phenotype_permanent_control(
+        &self,
+        phenotype: GraphVertices,
+        max_size: usize,
+        perturbation_variables: Vec<VariableId>,
+        attractor_search: AttractorSearch,
+    ) -> Result<PhenotypeControlMap, ControlError> {
         let now = Instant::now();
-        println!("Starting phenotype permanent control ceiled to size {} controlling {} different variables, started at: {}", max_size, perturbation_variables.len(), Local::now());
-        // A color set which will (eventually) hold the perturbations over
-        // `perturbation_variables` up to a certain size.
-        let mut admissible_perturbations = self.as_perturbed().mk_empty_colors();
+        if cfg!(feature = "print_progress") {
+            eprintln!("Starting phenotype permanent control ceiled to size {} controlling {} different variables, started at: {}", max_size, perturbation_variables.len(), Local::now());
+        }
+        let admissible_perturbations =
+            self.try_mk_admissible_perturbations(&perturbation_variables, max_size)?;
+
+        let result =
+            self.phenotype_permanent_control(phenotype, admissible_perturbations, attractor_search);
+        if cfg!(feature = "print_progress") {
+            eprintln!("Control map computation finished at {:?} ", Local::now());
+            eprintln!(
+                "Time elapsed for computing control map: {:?}",
+                now.elapsed()
+            );
+        }
+        Ok(result)
+    }
 
-        // Then, make a map which gives us a BDD variable of the "perturbation parameter"
+    /// The same as `try_ceiled_phenotype_permanent_control`, but the perturbations are
+    /// bounded by their total cost (see `PerturbationCosts`) instead of their size.
+    ///
+    /// Use `PhenotypeControlMap::cheapest_perturbations` to list the resulting controls,
+    /// cheapest first.
+    pub fn try_cost_bounded_phenotype_permanent_control(
+        &self,
+        phenotype: GraphVertices,
+        perturbation_variables: &[VariableId],
+        costs: &PerturbationCosts,
+        budget: usize,
+        attractor_search: AttractorSearch,
+    ) -> Result<PhenotypeControlMap, ControlError> {
+        let admissible_perturbations =
+            self.try_mk_admissible_perturbations_by_cost(perturbation_variables, costs, budget)?;
+        Ok(self.phenotype_permanent_control_within(
+            phenotype,
+            &admissible_perturbations,
+            &attractor_search,
+            PhenotypeControlSemantics::Permissive,
+        ))
+    }
+
+    /// Build the set of colors which perturb at most `max_size` of the given
+    /// `perturbation_variables`. Perturbation parameters of other variables and of regulations
+    /// are not restricted.
+    ///
+    /// Returns an error when `perturbation_variables` is empty or contains a variable that
+    /// cannot be perturbed.
+    pub fn try_mk_admissible_perturbations(
+        &self,
+        perturbation_variables: &[VariableId],
+        max_size: usize,
+    ) -> Result<GraphColors, ControlError> {
+        if perturbation_variables.is_empty() {
+            return Err(ControlError::NoPerturbableVariables);
+        }
+
+        // A map which gives us a BDD variable of the "perturbation parameter"
         // for each network variable.
         let symbolic_context = self.as_symbolic_context();
         let mut perturbation_bdd_vars = Vec::new();
-        for var in perturbation_variables {
-            if let Some(p) = self.get_perturbation_parameter(var) {
-                let table = symbolic_context.get_explicit_function_table(p);
-                assert_eq!(0, table.arity);
+        for var in perturbation_variables.iter().copied() {
+            let table = self
+                .get_perturbation_parameter(var)
+                .map(|p| symbolic_context.get_explicit_function_table(p));
+            if let Some(table) = table {
+                if table.arity != 0 {
+                    // Perturbation parameters are constants, anything else is a corrupted graph.
+                    let name = self.as_original().get_variable_name(var);
+                    return Err(ControlError::NotPerturbable(name));
+                }
                 perturbation_bdd_vars.push(table.symbolic_variables()[0]);
+            } else if var.to_index() < self.as_original().num_vars() {
+                let name = self.as_original().get_variable_name(var);
+                return Err(ControlError::NotPerturbable(name));
             } else {
-                panic!("Variable does not have a perturbation parameter.")
+                return Err(ControlError::InvalidVariableId(var));
             }
         }
 
         let bdd_vars = self.as_symbolic_context().bdd_variable_set();
         let admissible_bdd = mk_bdd_up_to_bound(bdd_vars, &perturbation_bdd_vars, max_size);
+        let unit = self.mk_unit_colors();
+        Ok(unit.intersect(&unit.copy(admissible_bdd)))
+    }
 
-        let colors = self.empty_colors().copy(admissible_bdd);
-        admissible_perturbations = admissible_perturbations.union(&colors);
-
-        let result = self.phenotype_permanent_control(
+    pub fn phenotype_permanent_control(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_search: AttractorSearch,
+    ) -> PhenotypeControlMap {
+        self.phenotype_permanent_control_with(
             phenotype,
             admissible_perturbations,
-            attractor_search_method,
-        );
-        let duration = now.elapsed();
-        println!("Control map computation finished at {:?} ", Local::now());
-        println!("Time elapsed for computing control map: {:?}", duration);
-        result
+            &attractor_search,
+        )
     }
 
-    pub fn phenotype_permanent_control(
+    /// The same as `phenotype_permanent_control`, but the phenotype-violating attractors are
+    /// computed using the given (possibly user-defined) `attractor_backend`.
+    pub fn phenotype_permanent_control_with(
         &self,
         phenotype: GraphVertices,
         admissible_perturbations: GraphColors,
-        attractor_search_method: &str,
+        attractor_backend: &dyn AttractorBackend,
     ) -> PhenotypeControlMap {
-        println!(
-            "all space {}",
-            self.as_perturbed()
-                .unit_colored_vertices()
-                .approx_cardinality()
-        );
-        println!(
-            "all vertices {}",
-            self.as_perturbed()
-                .unit_colored_vertices()
-                .vertices()
-                .approx_cardinality()
-        );
-        println!("phenotype vertices {}", phenotype.approx_cardinality());
+        self.phenotype_permanent_control_with_semantics(
+            phenotype,
+            admissible_perturbations,
+            attractor_backend,
+            PhenotypeControlSemantics::Permissive,
+        )
+    }
 
-        let now = Instant::now();
+    /// The same as `phenotype_permanent_control`, but the perturbation is applied in the given
+    /// `initial_states` (e.g. the healthy steady state). The map then contains perturbations
+    /// under which every attractor reachable from all `initial_states` lies in the `phenotype`.
+    pub fn phenotype_permanent_control_from(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        initial_states: &GraphColoredVertices,
+        attractor_search: AttractorSearch,
+    ) -> PhenotypeControlMap {
+        self.phenotype_permanent_control_with_semantics(
+            phenotype,
+            admissible_perturbations,
+            &attractor_search,
+            PhenotypeControlSemantics::InitialStates(initial_states.clone()),
+        )
+    }
 
-        let selected_attractor_search_method = if attractor_search_method == "heuristic" {
-            self.get_attractor_type_in_unperturbed_network()
-        } else {
-            attractor_search_method
-        };
+    /// The same as `phenotype_permanent_control_with`, but the `semantics` decides from which
+    /// initial states the perturbation has to work (see `PhenotypeControlSemantics`).
+    pub fn phenotype_permanent_control_with_semantics(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_backend: &dyn AttractorBackend,
+        semantics: PhenotypeControlSemantics,
+    ) -> PhenotypeControlMap {
+        // All states with admissible colors.
+        let admissible_perturbations = self
+            .empty_colored_vertices()
+            .copy(admissible_perturbations.into_bdd());
+        self.phenotype_permanent_control_within(
+            phenotype,
+            &admissible_perturbations,
+            attractor_backend,
+            semantics,
+        )
+    }
 
-        let mut phenotype_violating_attractors = self.mk_empty_colored_vertices();
-        if selected_attractor_search_method == "sinks" {
-            println!("------- Using fixed points implementation");
-            let phenotype_violating_space = self
-                .as_perturbed()
-                .unit_colored_vertices()
-                .minus_vertices(&phenotype)
-                .intersect_colors(&admissible_perturbations);
-            println!(
-                "space to explore attractors {}",
-                phenotype_violating_space.approx_cardinality()
-            );
-            phenotype_violating_attractors =
-                FixedPoints::symbolic(self.as_perturbed(), &phenotype_violating_space);
-        } else if selected_attractor_search_method == "complex" {
-            println!("------- Using all attractors implementation");
-            let complex_attractors = attractors::compute_restricted(
-                self.as_perturbed(),
-                self.mk_unit_colored_vertices()
-                    .intersect_colors(&admissible_perturbations),
+    /// The same as `phenotype_permanent_control_with_semantics`, but the admissible
+    /// perturbations are given as a set of states and colors (e.g. to restrict the values of
+    /// perturbed variables). The set must be closed under the perturbed dynamics.
+    fn phenotype_permanent_control_within(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: &GraphColoredVertices,
+        attractor_backend: &dyn AttractorBackend,
+        semantics: PhenotypeControlSemantics,
+    ) -> PhenotypeControlMap {
+        if cfg!(feature = "print_progress") {
+            eprintln!(
+                "all space {}",
+                self.unit_colored_vertices().approx_cardinality()
             );
-            for ca in complex_attractors {
-                let states_in_ca_but_not_phenotype = ca.minus_vertices(&phenotype);
-                let colors_with_states_outside_phenotype = states_in_ca_but_not_phenotype.colors();
-                let relevant_atts = ca.intersect_colors(&colors_with_states_outside_phenotype);
-                let violating_attractors = ca.intersect(&relevant_atts);
-                phenotype_violating_attractors =
-                    phenotype_violating_attractors.union(&violating_attractors);
-            }
-        } else {
-            panic!(
-                "Unknown attractor search method {:?}",
-                attractor_search_method
+            eprintln!(
+                "all vertices {}",
+                self.unit_colored_vertices().vertices().approx_cardinality()
             );
+            eprintln!("phenotype vertices {}", phenotype.approx_cardinality());
         }
 
-        print!("Attractor search computation took: {:?}", now.elapsed());
+        let now = Instant::now();
 
-        println!(
-            "violating atts cardinality {}",
-            phenotype_violating_attractors.approx_cardinality()
+        let universe = self
+            .as_perturbed()
+            .mk_unit_colored_vertices()
+            .intersect(admissible_perturbations);
+        let phenotype_violating_attractors = attractor_backend.phenotype_violating_attractors(
+            self,
+            self.as_perturbed(),
+            &universe,
+            &phenotype,
         );
 
+        if cfg!(feature = "print_progress") {
+            eprint!("Attractor search computation took: {:?}", now.elapsed());
+            eprintln!(
+                "violating atts cardinality {}",
+                phenotype_violating_attractors.approx_cardinality()
+            );
+        }
+
         let now = Instant::now();
 
         let phenotype_violating_space =
             Reachability::reach_bwd(self.as_perturbed(), &phenotype_violating_attractors);
-        println!(
-            "violating space {}",
-            phenotype_violating_space.approx_cardinality()
-        );
+        if cfg!(feature = "print_progress") {
+            eprintln!(
+                "violating space {}",
+                phenotype_violating_space.approx_cardinality()
+            );
+        }
 
-        let phenotype_respecting_space = self
-            .as_perturbed()
-            .unit_colored_vertices()
-            .intersect_colors(&admissible_perturbations)
-            .minus(&phenotype_violating_space);
-        println!(
-            "ok space {}",
-            phenotype_respecting_space.approx_cardinality()
-        );
+        let phenotype_respecting_space = universe.minus(&phenotype_violating_space);
+        if cfg!(feature = "print_progress") {
+            eprintln!(
+                "ok space {}",
+                phenotype_respecting_space.approx_cardinality()
+            );
+            eprint!("space computation took: {:?}", now.elapsed());
+        }
 
-        print!("space computation took: {:?}", now.elapsed());
+        let perturbation_set = match &semantics {
+            PhenotypeControlSemantics::Permissive => phenotype_respecting_space,
+            PhenotypeControlSemantics::AllInitialStates => self.guaranteed_phenotype_control(
+                &self.mk_unit_colored_vertices(),
+                &phenotype,
+                &phenotype_respecting_space,
+            ),
+            PhenotypeControlSemantics::InitialStates(initial_states) => self
+                .guaranteed_phenotype_control(
+                    initial_states,
+                    &phenotype,
+                    &phenotype_respecting_space,
+                ),
+        };
 
         PhenotypeControlMap {
-            perturbation_set: phenotype_respecting_space,
+            perturbation_set,
             context: self.clone(),
+            semantics,
+            mode: ControlMode::Permanent,
         }
     }
 
-    fn get_attractor_type_in_unperturbed_network(&self) -> &str {
-        let unperturbed_attractors = attractors::compute(self.as_original());
-        let mut unperturbed_attractors_all = self.as_original().mk_empty_colored_vertices();
-        for ua in unperturbed_attractors {
-            unperturbed_attractors_all = unperturbed_attractors_all.union(&ua);
-        }
-        let unperturbed_attractors_fps = FixedPoints::symbolic(
-            self.as_original(),
-            self.as_original().unit_colored_vertices(),
-        );
-
-        println!(
-            "FPs {:?}",
-            unperturbed_attractors_fps.vertices().approx_cardinality()
+    /// Restrict the `phenotype_respecting_space` to the perturbations that lead into it from
+    /// every state of `initial_states`, such that the phenotype is reachable afterwards.
+    ///
+    /// The values of unperturbed variables are not relevant for the result, so every working
+    /// perturbation is included with all such values.
+    fn guaranteed_phenotype_control(
+        &self,
+        initial_states: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+        phenotype_respecting_space: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        /*
+           Without the reachability requirement, a perturbation which (e.g.) only creates
+           attractors that are missed by the attractor search would count as a success.
+        */
+        let reaches_phenotype = Reachability::reach_bwd(
+            self.as_perturbed(),
+            &phenotype_respecting_space.intersect_vertices(phenotype),
         );
-        println!(
-            "ALL {:?}",
-            unperturbed_attractors_all.vertices().approx_cardinality()
-        );
-        if unperturbed_attractors_all
-            .vertices()
-            .is_subset(&unperturbed_attractors_fps.vertices())
-        {
-            "sinks"
-        } else {
-            "complex"
-        }
+        let target = phenotype_respecting_space.intersect(&reaches_phenotype);
+        let working =
+            self.post_perturbation_from_set(initial_states, &target, SourceQuantifier::ForAll);
+        self.forget_values(&working, false)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::aeon::phentoype::build_phenotype;
+    use crate::aeon::phentoype::{build_phenotype, try_build_phenotype};
+    use crate::error::ControlError;
     use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::_impl_attractor_search::{
+        HeuristicAttractorSearch, PrecomputedAttractors,
+    };
+    use crate::phenotype_control::{
+        AttractorSearch, ComplexAttractorSearch, PerturbationCosts, PhenotypeControlSemantics,
+        PhenotypeMembership,
+    };
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
     use biodivine_lib_param_bn::BooleanNetwork;
     use std::collections::HashMap;
     use std::convert::TryFrom;
@@ -199,8 +332,7 @@ mod tests {
         let model_string = &std::fs::read_to_string("models/myeloid_witness.aeon").unwrap();
         let model = BooleanNetwork::try_from(model_string.as_str()).unwrap();
         println!(
-            "========= {}({}) =========",
-            "models/myeloid_witness.aeon",
+            "========= models/myeloid_witness.aeon({}) =========",
             model.num_vars()
         );
         let perturbations = PerturbationGraph::new(&model);
@@ -210,8 +342,8 @@ mod tests {
         );
         let control = perturbations.phenotype_permanent_control(
             erythrocyte_phenotype,
-            perturbations.as_perturbed().mk_unit_colors(),
-            "sinks",
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Sinks,
         );
 
         // Trivial working control
@@ -252,13 +384,12 @@ mod tests {
         let model_string = &std::fs::read_to_string("models/myeloid_witness.aeon").unwrap();
         let model = BooleanNetwork::try_from(model_string.as_str()).unwrap();
         println!(
-            "========= {}({}) =========",
-            "models/myeloid_witness.aeon",
+            "========= models/myeloid_witness.aeon({}) =========",
             model.num_vars()
         );
         let mut all_vars = Vec::new();
         for v in model.variables() {
-            all_vars.push(v.clone());
+            all_vars.push(v);
         }
 
         let perturbations = PerturbationGraph::new(&model);
@@ -270,7 +401,7 @@ mod tests {
             erythrocyte_phenotype,
             3,
             all_vars,
-            "sinks",
+            AttractorSearch::Sinks,
         );
 
         // Trivial working control
@@ -305,4 +436,347 @@ mod tests {
 
         assert_eq!(0.0, not_working_colors.approx_cardinality());
     }
+
+    #[test]
+    pub fn test_invalid_input_errors() {
+        let model = myeloid_witness();
+        let eklf = model.as_graph().find_variable("EKLF").unwrap();
+        let gata1 = model.as_graph().find_variable("GATA1").unwrap();
+        let perturbations = PerturbationGraph::with_restricted_variables(&model, &[eklf]);
+
+        let unknown = try_build_phenotype(
+            perturbations.as_perturbed(),
+            HashMap::from([("NOT_A_VARIABLE", true)]),
+        );
+        assert_eq!(
+            Err(ControlError::UnknownVariable("NOT_A_VARIABLE".to_string())),
+            unknown
+        );
+
+        let phenotype = erythrocyte_phenotype(&perturbations);
+        let not_perturbable = perturbations.try_ceiled_phenotype_permanent_control(
+            phenotype.clone(),
+            1,
+            vec![gata1],
+            AttractorSearch::Sinks,
+        );
+        assert_eq!(
+            Some(ControlError::NotPerturbable("GATA1".to_string())),
+            not_perturbable.err()
+        );
+
+        let no_variables = perturbations.try_ceiled_phenotype_permanent_control(
+            phenotype.clone(),
+            1,
+            vec![],
+            AttractorSearch::Sinks,
+        );
+        assert_eq!(
+            Some(ControlError::NoPerturbableVariables),
+            no_variables.err()
+        );
+
+        let unknown_method = "fixed-points".parse::<AttractorSearch>();
+        assert_eq!(
+            Err(ControlError::UnknownAttractorSearch(
+                "fixed-points".to_string()
+            )),
+            unknown_method
+        );
+
+        for fraction in [-0.5, 1.5, f64::NAN] {
+            let membership = PhenotypeMembership::AtLeast(fraction);
+            assert!(matches!(
+                ComplexAttractorSearch::try_new(membership),
+                Err(ControlError::InvalidPhenotypeMembership(_))
+            ));
+        }
+        assert!(ComplexAttractorSearch::try_new(PhenotypeMembership::AtLeast(1.0)).is_ok());
+
+        assert!(
+            PerturbationGraph::try_with_perturbable_names(&model, &["NOT_A_VARIABLE"]).is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_attractor_backends_oscillation() {
+        // `x` and `y` oscillate, `z` is bistable and decides the phenotype.
+        let model =
+            BooleanNetwork::try_from("x -> y \n y -| x \n z -> z \n $x: !y \n $y: x \n $z: z")
+                .unwrap();
+        let perturbations = PerturbationGraph::new(&model);
+        let phenotype = build_phenotype(perturbations.as_perturbed(), HashMap::from([("z", true)]));
+
+        // The network has complex attractors.
+        assert_eq!(
+            AttractorSearch::Complex,
+            HeuristicAttractorSearch.select(&perturbations)
+        );
+
+        let complex = perturbations.phenotype_permanent_control(
+            phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Complex,
+        );
+        let heuristic = perturbations.phenotype_permanent_control(
+            phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Heuristic,
+        );
+        assert_eq!(complex.as_bdd(), heuristic.as_bdd());
+
+        let precomputed = PrecomputedAttractors::new(crate::aeon::attractors::compute(
+            perturbations.as_perturbed(),
+        ));
+        let user_defined = perturbations.phenotype_permanent_control_with(
+            phenotype,
+            perturbations.mk_unit_colors(),
+            &precomputed,
+        );
+        assert_eq!(complex.as_bdd(), user_defined.as_bdd());
+
+        let fix_z = HashMap::from([(String::from("z"), true)]);
+        assert_eq!(
+            1.0,
+            complex
+                .perturbation_working_colors(&fix_z)
+                .approx_cardinality()
+        );
+        let fix_x = HashMap::from([(String::from("x"), true)]);
+        assert_eq!(
+            0.0,
+            complex
+                .perturbation_working_colors(&fix_x)
+                .approx_cardinality()
+        );
+    }
+
+    #[test]
+    pub fn test_perturbation_values_myeloid() {
+        let model = myeloid_witness();
+        let eklf = model.as_graph().find_variable("EKLF").unwrap();
+        let gata1 = model.as_graph().find_variable("GATA1").unwrap();
+        let fli1 = model.as_graph().find_variable("Fli1").unwrap();
+        let eklf_true = HashMap::from([(String::from("EKLF"), true)]);
+        let gata1_fli1 =
+            HashMap::from([(String::from("GATA1"), true), (String::from("Fli1"), false)]);
+
+        for (eklf_value, eklf_works) in [(None, 1.0), (Some(true), 1.0), (Some(false), 0.0)] {
+            let perturbations = PerturbationGraph::try_with_perturbation_values(
+                &model,
+                &[(eklf, eklf_value), (gata1, Some(true)), (fli1, None)],
+            )
+            .unwrap();
+            let control = perturbations.phenotype_permanent_control(
+                erythrocyte_phenotype(&perturbations),
+                perturbations.mk_unit_colors(),
+                AttractorSearch::Sinks,
+            );
+            assert_eq!(
+                eklf_works,
+                control
+                    .perturbation_working_colors(&eklf_true)
+                    .approx_cardinality()
+            );
+            assert_eq!(
+                1.0,
+                control
+                    .perturbation_working_colors(&gata1_fli1)
+                    .approx_cardinality()
+            );
+
+            // The control map never contains a perturbation to a forbidden value.
+            assert!(perturbations
+                .fix_perturbation(gata1, Some(false))
+                .is_empty());
+            assert!(control
+                .as_colored_vertices()
+                .intersect(&perturbations.fix_variable(gata1, false))
+                .minus_colors(&perturbations.not_perturbed(gata1))
+                .is_empty());
+        }
+    }
+
+    #[test]
+    pub fn test_cost_bounded_permanent_myeloid() {
+        let model = myeloid_witness();
+        let perturbations =
+            PerturbationGraph::try_with_perturbable_names(&model, &["EKLF", "GATA1", "Fli1"])
+                .unwrap();
+        let variables = perturbations
+            .variables()
+            .filter(|it| perturbations.get_perturbation_parameter(*it).is_some())
+            .collect::<Vec<_>>();
+        let eklf = model.as_graph().find_variable("EKLF").unwrap();
+        let phenotype = erythrocyte_phenotype(&perturbations);
+        let mut costs = PerturbationCosts::new();
+        costs.set_variable_cost(eklf, 3);
+
+        let control = perturbations
+            .try_cost_bounded_phenotype_permanent_control(
+                phenotype,
+                &variables,
+                &costs,
+                3,
+                AttractorSearch::Sinks,
+            )
+            .unwrap();
+        let cheapest = control.cheapest_perturbations(&costs, 3);
+        for pair in cheapest.windows(2) {
+            assert!(pair[0].cost <= pair[1].cost);
+        }
+
+        // The cheapest control avoids the expensive EKLF.
+        let gata1_fli1 =
+            HashMap::from([(String::from("GATA1"), true), (String::from("Fli1"), false)]);
+        assert_eq!(gata1_fli1, cheapest[0].perturbation);
+        assert_eq!(2, cheapest[0].cost);
+        let eklf_true = cheapest
+            .iter()
+            .find(|it| it.perturbation == HashMap::from([(String::from("EKLF"), true)]))
+            .unwrap();
+        assert_eq!(3, eklf_true.cost);
+
+        // Perturbations above the budget are not admissible.
+        assert_eq!(
+            0.0,
+            control
+                .perturbation_working_colors(&HashMap::from([
+                    (String::from("EKLF"), true),
+                    (String::from("GATA1"), true),
+                ]))
+                .approx_cardinality()
+        );
+    }
+
+    #[test]
+    pub fn test_control_semantics_oscillation() {
+        let model =
+            BooleanNetwork::try_from("x -> y \n y -| x \n z -> z \n $x: !y \n $y: x \n $z: z")
+                .unwrap();
+        let perturbations = PerturbationGraph::new(&model);
+        let phenotype = build_phenotype(perturbations.as_perturbed(), HashMap::from([("z", true)]));
+        let no_perturbation = HashMap::new();
+        let z_true = HashMap::from([(String::from("z"), true)]);
+
+        // Sink search misses the oscillation, so every state looks like it respects the
+        // phenotype, including states with `z = false`.
+        let permissive = perturbations.phenotype_permanent_control(
+            phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Sinks,
+        );
+        assert_eq!(
+            &PhenotypeControlSemantics::Permissive,
+            permissive.semantics()
+        );
+        assert_eq!(
+            1.0,
+            permissive
+                .perturbation_working_colors(&no_perturbation)
+                .approx_cardinality()
+        );
+
+        // With the guarantee, the phenotype must be reachable from every initial state.
+        let guaranteed = perturbations.phenotype_permanent_control_with_semantics(
+            phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            &AttractorSearch::Sinks,
+            PhenotypeControlSemantics::AllInitialStates,
+        );
+        assert_eq!(
+            &PhenotypeControlSemantics::AllInitialStates,
+            guaranteed.semantics()
+        );
+        assert_eq!(
+            0.0,
+            guaranteed
+                .perturbation_working_colors(&no_perturbation)
+                .approx_cardinality()
+        );
+        assert_eq!(
+            1.0,
+            guaranteed
+                .perturbation_working_colors(&z_true)
+                .approx_cardinality()
+        );
+        assert!(guaranteed
+            .as_colored_vertices()
+            .is_subset(permissive.as_colored_vertices()));
+
+        // Starting in the phenotype, no perturbation is necessary.
+        let initial = PhenotypeControlSemantics::InitialStates(
+            perturbations
+                .mk_unit_colored_vertices()
+                .intersect_vertices(&phenotype),
+        );
+        let from_phenotype = perturbations.phenotype_permanent_control_with_semantics(
+            phenotype,
+            perturbations.mk_unit_colors(),
+            &AttractorSearch::Sinks,
+            initial.clone(),
+        );
+        assert_eq!(&initial, from_phenotype.semantics());
+        assert_eq!(
+            1.0,
+            from_phenotype
+                .perturbation_working_colors(&no_perturbation)
+                .approx_cardinality()
+        );
+    }
+
+    #[test]
+    pub fn test_phenotype_membership_oscillation() {
+        // `x` and `y` oscillate through all four combinations of values.
+        let model =
+            BooleanNetwork::try_from("x -> y \n y -| x \n z -> z \n $x: !y \n $y: x \n $z: z")
+                .unwrap();
+        let perturbations = PerturbationGraph::new(&model);
+        let x = build_phenotype(perturbations.as_perturbed(), HashMap::from([("x", true)]));
+        let no_perturbation = HashMap::new();
+        let x_true = HashMap::from([(String::from("x"), true)]);
+
+        let working = |membership: PhenotypeMembership, perturbation: &HashMap<String, bool>| {
+            perturbations
+                .phenotype_permanent_control_with(
+                    x.clone(),
+                    perturbations.mk_unit_colors(),
+                    &ComplexAttractorSearch::new(membership),
+                )
+                .perturbation_working_colors(perturbation)
+                .approx_cardinality()
+        };
+
+        // Half of the oscillation lies in the phenotype.
+        assert_eq!(0.0, working(PhenotypeMembership::StaysIn, &no_perturbation));
+        assert_eq!(1.0, working(PhenotypeMembership::Visits, &no_perturbation));
+        assert_eq!(
+            1.0,
+            working(PhenotypeMembership::AtLeast(0.5), &no_perturbation)
+        );
+        assert_eq!(
+            0.0,
+            working(PhenotypeMembership::AtLeast(0.75), &no_perturbation)
+        );
+
+        // Fixing `x` leads to a fixed point in the phenotype, which is fine for all modes.
+        assert_eq!(1.0, working(PhenotypeMembership::StaysIn, &x_true));
+        assert_eq!(1.0, working(PhenotypeMembership::AtLeast(0.75), &x_true));
+
+        // `StaysIn` is the same as the default complex attractor search.
+        let complex = perturbations.phenotype_permanent_control(
+            x.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Complex,
+        );
+        let stays_in = perturbations.phenotype_permanent_control_with(
+            x.clone(),
+            perturbations.mk_unit_colors(),
+            &ComplexAttractorSearch::new(PhenotypeMembership::StaysIn),
+        );
+        assert_eq!(
+            complex.as_colored_vertices(),
+            stays_in.as_colored_vertices()
+        );
+    }
 }
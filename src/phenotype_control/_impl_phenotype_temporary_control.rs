@@ -0,0 +1,179 @@
+use crate::aeon::reachability::{backward, forward_closed};
+use crate::control::ControlMode;
+use crate::error::ControlError;
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::{
+    AttractorBackend, AttractorSearch, PhenotypeControlMap, PhenotypeControlSemantics,
+};
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::reachability::Reachability;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices,
+};
+use biodivine_lib_param_bn::VariableId;
+
+impl PerturbationGraph {
+    /// Compute temporary phenotype control map restricted to perturbations of at most
+    /// `max_size` variables from `perturbation_variables`.
+    ///
+    /// Returns an error when `perturbation_variables` is empty or contains a variable
+    /// that cannot be perturbed.
+    pub fn try_ceiled_phenotype_temporary_control(
+        &self,
+        phenotype: GraphVertices,
+        max_size: usize,
+        perturbation_variables: Vec<VariableId>,
+        attractor_search: AttractorSearch,
+    ) -> Result<PhenotypeControlMap, ControlError> {
+        let admissible_perturbations =
+            self.try_mk_admissible_perturbations(&perturbation_variables, max_size)?;
+        Ok(self.phenotype_temporary_control(phenotype, admissible_perturbations, attractor_search))
+    }
+
+    /// Compute temporary phenotype control map. That is, perturbations which are applied,
+    /// held "as long as necessary" and then released, such that every attractor of the
+    /// unperturbed network that is reached afterwards lies in the `phenotype`.
+    pub fn phenotype_temporary_control(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_search: AttractorSearch,
+    ) -> PhenotypeControlMap {
+        self.phenotype_temporary_control_with(
+            phenotype,
+            admissible_perturbations,
+            &attractor_search,
+        )
+    }
+
+    /// The same as `phenotype_temporary_control`, but the phenotype-violating attractors are
+    /// computed using the given (possibly user-defined) `attractor_backend`.
+    pub fn phenotype_temporary_control_with(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: GraphColors,
+        attractor_backend: &dyn AttractorBackend,
+    ) -> PhenotypeControlMap {
+        /*
+           Similar to source-target temporary control, the perturbation must be held until the
+           network reaches a trap set of the unperturbed network. Here, the trap set is the strong
+           basin of the phenotype-respecting attractors.
+        */
+        let phenotype_respecting_space = self.phenotype_strong_basin_with(
+            phenotype,
+            &admissible_perturbations,
+            attractor_backend,
+        );
+
+        // Only the states of the perturbed graph can be the result of a perturbation.
+        let phenotype_respecting_space =
+            phenotype_respecting_space.intersect(self.as_perturbed().unit_colored_vertices());
+        let perturbed_weak_basin = backward(self.as_perturbed(), &phenotype_respecting_space);
+        let perturbed_strong_basin = forward_closed(self.as_perturbed(), &perturbed_weak_basin);
+
+        PhenotypeControlMap {
+            perturbation_set: perturbed_strong_basin.intersect_colors(&admissible_perturbations),
+            context: self.clone(),
+            semantics: PhenotypeControlSemantics::Permissive,
+            mode: ControlMode::Temporary,
+        }
+    }
+
+    /// Compute the states of the original (unperturbed) graph from which only attractors
+    /// that lie in the `phenotype` are reachable, i.e. the strong basin of the
+    /// phenotype-respecting attractors.
+    pub fn phenotype_strong_basin_with(
+        &self,
+        phenotype: GraphVertices,
+        admissible_perturbations: &GraphColors,
+        attractor_backend: &dyn AttractorBackend,
+    ) -> GraphColoredVertices {
+        let universe = self
+            .as_original()
+            .unit_colored_vertices()
+            .intersect_colors(admissible_perturbations);
+        let phenotype_violating_attractors = attractor_backend.phenotype_violating_attractors(
+            self,
+            self.as_original(),
+            &universe,
+            &phenotype,
+        );
+        let phenotype_violating_space =
+            Reachability::reach_bwd(self.as_original(), &phenotype_violating_attractors);
+        universe.minus(&phenotype_violating_space)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::AttractorSearch;
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_temporary_myeloid() {
+        let model = myeloid_witness();
+        let mut all_vars = Vec::new();
+        for v in model.variables() {
+            all_vars.push(v);
+        }
+
+        let perturbations = PerturbationGraph::new(&model);
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+        let control = perturbations.phenotype_temporary_control(
+            erythrocyte_phenotype.clone(),
+            perturbations.mk_unit_colors(),
+            AttractorSearch::Sinks,
+        );
+        let ceiled_control = perturbations
+            .try_ceiled_phenotype_temporary_control(
+                erythrocyte_phenotype,
+                3,
+                all_vars,
+                AttractorSearch::Sinks,
+            )
+            .unwrap();
+
+        // Unlike permanent control, holding EKLF is not sufficient, because EKLF can be
+        // switched off again once the perturbation is released.
+        let eklf = HashMap::from([(String::from("EKLF"), true)]);
+        assert_eq!(
+            0.0,
+            control
+                .perturbation_working_colors(&eklf)
+                .approx_cardinality()
+        );
+
+        // Non-trivial working control
+        let working = HashMap::from([
+            (String::from("Fli1"), false),
+            (String::from("GATA1"), true),
+            (String::from("GATA2"), true),
+        ]);
+        assert_eq!(
+            1.0,
+            control
+                .perturbation_working_colors(&working)
+                .approx_cardinality()
+        );
+        assert_eq!(
+            1.0,
+            ceiled_control
+                .perturbation_working_colors(&working)
+                .approx_cardinality()
+        );
+
+        let not_working =
+            HashMap::from([(String::from("GATA1"), true), (String::from("Fli1"), false)]);
+        assert_eq!(
+            0.0,
+            control
+                .perturbation_working_colors(&not_working)
+                .approx_cardinality()
+        );
+
+        let not_working_colors = control.perturbation_working_colors(&HashMap::from([]));
+        assert_eq!(0.0, not_working_colors.approx_cardinality());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::control::ControlMode;
+use crate::error::ControlError;
+use crate::perturbation::_impl_serialization::{check_kind, invalid, read_json, write_json};
+use crate::perturbation::PerturbationGraph;
+use crate::phenotype_control::{PhenotypeControlMap, PhenotypeControlSemantics};
+use serde_json::{json, Value};
+use std::path::Path;
+
+impl PhenotypeControlMap {
+    /// Write this map to a JSON file. Besides the map itself, the file contains the model
+    /// and perturbable variables, so that the map can be restored using
+    /// `PhenotypeControlMap::load`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ControlError> {
+        let semantics = match &self.semantics {
+            PhenotypeControlSemantics::Permissive => json!("permissive"),
+            PhenotypeControlSemantics::AllInitialStates => json!("all-initial-states"),
+            PhenotypeControlSemantics::InitialStates(states) => {
+                json!({ "initial-states": states.as_bdd().to_string() })
+            }
+        };
+        let value = json!({
+            "kind": "phenotype-control-map",
+            "mode": self.mode.to_string(),
+            "semantics": semantics,
+            "graph": self.context.to_json(),
+            "map": self.perturbation_set.as_bdd().to_string(),
+        });
+        write_json(path, &value)
+    }
+
+    /// Read a map created by `PhenotypeControlMap::save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PhenotypeControlMap, ControlError> {
+        let value = read_json(path)?;
+        check_kind(&value, "phenotype-control-map")?;
+        let mode = value["mode"]
+            .as_str()
+            .ok_or_else(|| invalid("Missing control mode."))?
+            .parse::<ControlMode>()?;
+        let context = PerturbationGraph::try_from_json(&value["graph"])?;
+        let semantics = match &value["semantics"] {
+            Value::String(name) if name == "permissive" => PhenotypeControlSemantics::Permissive,
+            Value::String(name) if name == "all-initial-states" => {
+                PhenotypeControlSemantics::AllInitialStates
+            }
+            Value::Object(_) => {
+                let states = context.try_bdd_from_json(&value["semantics"]["initial-states"])?;
+                PhenotypeControlSemantics::InitialStates(
+                    context.empty_colored_vertices().copy(states),
+                )
+            }
+            _ => return Err(invalid("Invalid control semantics.")),
+        };
+        let perturbation_set = context.try_bdd_from_json(&value["map"])?;
+        Ok(PhenotypeControlMap {
+            perturbation_set: context.empty_colored_vertices().copy(perturbation_set),
+            context,
+            semantics,
+            mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::{ControlMap, ControlMode};
+    use crate::error::ControlError;
+    use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::{
+        AttractorSearch, PhenotypeControlMap, PhenotypeControlSemantics,
+    };
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_save_load_phenotype_control_map_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let phenotype = erythrocyte_phenotype(&perturbations);
+        let initial_states = perturbations
+            .mk_unit_colored_vertices()
+            .intersect_vertices(&phenotype);
+        let control = perturbations.phenotype_permanent_control_with_semantics(
+            phenotype,
+            perturbations.mk_unit_colors(),
+            &AttractorSearch::Sinks,
+            PhenotypeControlSemantics::InitialStates(initial_states),
+        );
+
+        let path = std::env::temp_dir().join("pbn_control_test_phenotype_control_map.json");
+        control.save(&path).unwrap();
+        let loaded = PhenotypeControlMap::load(&path).unwrap();
+        // A phenotype control map is not a source-target control map.
+        assert!(matches!(
+            ControlMap::load(&path),
+            Err(ControlError::InvalidControlMap(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(ControlMode::Permanent, loaded.mode());
+        assert_eq!(control.semantics(), loaded.semantics());
+        assert_eq!(control.as_bdd(), loaded.as_bdd());
+        let eklf = HashMap::from([(String::from("EKLF"), true)]);
+        assert_eq!(
+            control.perturbation_working_colors(&eklf),
+            loaded.perturbation_working_colors(&eklf)
+        );
+    }
+}
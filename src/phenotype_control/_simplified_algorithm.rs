@@ -1,39 +1,76 @@
 use crate::perturbation::PerturbationGraph;
 use crate::phenotype_control::_symbolic_utils::mk_bdd_of_bound;
+use crate::phenotype_control::{
+    AttractorBackend, BoundedControlResult, BoundedPerturbation, PerturbationCosts,
+    PhenotypeControlSemantics,
+};
+use biodivine_lib_bdd::BddVariable;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::projected_iteration::RawProjection;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphVertices, SymbolicAsyncGraph,
+};
+use biodivine_lib_param_bn::VariableId;
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
+/// Compute phenotype control for every perturbation size up to `size_bound` (inclusive).
+///
+/// The result contains one `BoundedControlResult` for each perturbation size, in increasing
+/// order. Each result holds the full control map as well as the enumerated perturbations
+/// of the given size with their working colors and robustness. Perturbed regulations (see
+/// `PerturbationGraph::try_with_edge_perturbations`) count towards the perturbation size.
 pub fn bounded_phenotype_control(
     graph: &PerturbationGraph,
     phenotype: &GraphVertices,
     size_bound: usize,
-) {
-    // A map which gives us the symbolic variable of the perturbation parameter.
-    let perturbation_var_map = graph
-        .variables()
-        .filter_map(|var| graph.get_perturbation_parameter(var).map(|it| (var, it)))
-        .map(|(var, param)| {
-            (
-                var,
-                graph
-                    .as_symbolic_context()
-                    .get_explicit_function_table(param)
-                    .symbolic_variables()[0],
-            )
-        })
-        .collect::<HashMap<_, _>>();
+) -> Vec<BoundedControlResult> {
+    bounded_phenotype_control_within(graph, phenotype, None, None, size_bound)
+}
+
+/// The same as `bounded_phenotype_control`, but the phenotype-violating attractors are
+/// computed using the given `attractor_backend` (e.g. `AttractorSearch::Sinks`).
+///
+/// By default, every trap set outside of the phenotype is treated as a violating attractor,
+/// which is always correct, but can reject perturbations that only admit such trap sets
+/// without admitting an actual violating attractor (see `PhenotypeMembership`).
+pub fn bounded_phenotype_control_with(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    size_bound: usize,
+    attractor_backend: &dyn AttractorBackend,
+) -> Vec<BoundedControlResult> {
+    bounded_phenotype_control_within(graph, phenotype, None, Some(attractor_backend), size_bound)
+}
 
+/// The same as `bounded_phenotype_control`, but the perturbation is applied in the given
+/// `initial_states` (e.g. the healthy steady state) instead of the whole state space.
+///
+/// The control maps use `PhenotypeControlSemantics::InitialStates`, i.e. they are the same
+/// as the maps of `PerturbationGraph::phenotype_permanent_control_from` with a complete
+/// attractor search: a perturbation works if every attractor reachable from the
+/// `initial_states` (after the perturbation is applied) lies in the `phenotype`.
+pub fn bounded_phenotype_control_from(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    initial_states: &GraphColoredVertices,
+    size_bound: usize,
+) -> Vec<BoundedControlResult> {
+    bounded_phenotype_control_within(graph, phenotype, Some(initial_states), None, size_bound)
+}
+
+fn bounded_phenotype_control_within(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    initial_states: Option<&GraphColoredVertices>,
+    attractor_backend: Option<&dyn AttractorBackend>,
+    size_bound: usize,
+) -> Vec<BoundedControlResult> {
     let bdd_vars = graph.as_symbolic_context().bdd_variable_set();
     // The list of symbolic variables of perturbation parameters.
-    let perturbation_vars = {
-        let mut values = perturbation_var_map.values().cloned().collect::<Vec<_>>();
-        values.sort();
-        values
-    };
+    let perturbation_vars = all_perturbation_vars(graph);
 
+    let mut results = Vec::new();
     for perturbation_size in 0..(size_bound + 1) {
         let start = SystemTime::now();
         println!("Perturbation size: {}", perturbation_size);
@@ -47,84 +84,37 @@ pub fn bounded_phenotype_control(
                 admissible_perturbations.cardinality() / factor
             );
         }
-        let admissible_perturbations = graph.empty_colors().copy(admissible_perturbations);
-
-        // This is a trap set of all state-color-perturbation combinations that are
-        // guaranteed to stay in the phenotype.
-        let mut trap = graph
-            .unit_colored_vertices()
-            .intersect_colors(&admissible_perturbations)
-            .intersect_vertices(phenotype);
-
-        'trap: loop {
-            for var in graph.variables().rev() {
-                let can_leave = graph.as_perturbed().var_can_post_out(var, &trap);
-                if !can_leave.is_empty() {
-                    trap = trap.minus(&can_leave);
-                    if trap.symbolic_size() > 100_000 {
-                        println!(
-                            "[{}] >> Trap phenotype progress: {} / {}",
-                            perturbation_size,
-                            trap.symbolic_size(),
-                            trap.approx_cardinality()
-                        );
-                    }
-                    continue 'trap;
-                }
-            }
-            break;
-        }
-
-        let mut trap = graph
+        // Only the colors of the model are admissible (see `PerturbationGraph::as_perturbed`).
+        let admissible_perturbations = graph
+            .unit_colors()
+            .copy(admissible_perturbations)
+            .intersect(graph.unit_colors());
+        let admissible_perturbations = graph
+            .as_perturbed()
             .unit_colored_vertices()
-            .intersect_colors(&admissible_perturbations)
-            .minus(&trap);
-
-        'trap: loop {
-            for var in graph.variables().rev() {
-                let can_leave = graph.as_perturbed().var_can_post_out(var, &trap);
-                if !can_leave.is_empty() {
-                    trap = trap.minus(&can_leave);
-                    if trap.symbolic_size() > 100_000 {
-                        println!(
-                            "[{}] >> Trap non-phenotype progress: {} / {}",
-                            perturbation_size,
-                            trap.symbolic_size(),
-                            trap.approx_cardinality()
-                        );
-                    }
-                    continue 'trap;
-                }
-            }
-            break;
-        }
-
-        let mut inverse_control = trap.into_bdd();
-        for var in graph.variables() {
-            let state_var = graph.as_symbolic_context().get_state_variable(var);
-            if let Some(perturbation_var) = perturbation_var_map.get(&var) {
-                // If the variable can be perturbed, we split into two cases and eliminate
-                // it in the unperturbed cases.
-
-                let is_perturbed = inverse_control.var_select(*perturbation_var, true);
-                let is_not_perturbed = inverse_control
-                    .var_select(*perturbation_var, false)
-                    .var_exists(state_var);
-                inverse_control = is_perturbed.or(&is_not_perturbed);
-            } else {
-                // If the variable cannot be perturbed, we can eliminate it everywhere.
-                inverse_control = inverse_control.var_exists(state_var);
-            }
-        }
+            .intersect_colors(&admissible_perturbations);
 
-        let inverse_control_map = graph.empty_colored_vertices().copy(inverse_control);
-
-        // Control map consists of admissible state-color pairs that are also admissible
-        // for our perturbation size and are not in the inverse map.
-        let control_map = graph
-            .unit_colored_vertices()
-            .intersect_colors(&admissible_perturbations)
-            .minus(&inverse_control_map);
+        let trap_search = ViolatingTrapSearch {
+            label: perturbation_size,
+        };
+        let attractor_backend = attractor_backend.unwrap_or(&trap_search);
+        let control_map = match initial_states {
+            Some(initial_states) => graph
+                .phenotype_permanent_control_with_semantics(
+                    phenotype.clone(),
+                    admissible_perturbations.colors(),
+                    attractor_backend,
+                    PhenotypeControlSemantics::InitialStates(initial_states.clone()),
+                )
+                .as_colored_vertices()
+                .clone(),
+            None => phenotype_control_map(
+                graph,
+                phenotype,
+                &admissible_perturbations,
+                attractor_backend,
+            ),
+        };
 
         {
             // Compute the number of valuations of the perturbation parameters.
@@ -142,88 +132,529 @@ pub fn bounded_phenotype_control(
             );
         }
 
-        let control_map_bdd = control_map.clone().into_bdd();
-        let perturbation_vars_projection =
-            RawProjection::new(perturbation_vars.clone(), &control_map_bdd);
-
-        let all_colors_size =
-            graph.unit_colors().approx_cardinality() / 2.0f64.powi(perturbation_vars.len() as i32);
-        let mut best_robustness = 0.0;
-        let mut with_best_robustness = 0;
-
-        for is_perturbed_vector in perturbation_vars_projection.iter() {
-            let perturbed_variables = perturbation_var_map
-                .iter()
-                .filter_map(|(var, p_var)| {
-                    if is_perturbed_vector.get_value(*p_var).unwrap() {
-                        Some(*var)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            // This should remove all perturbation symbolic variables from the set.
-            let control_subset = control_map_bdd.restrict(&is_perturbed_vector.to_values());
-
-            let perturbed_state_vars = perturbed_variables
-                .iter()
-                .map(|var| graph.as_symbolic_context().get_state_variable(*var))
-                .collect::<Vec<_>>();
-
-            let state_vars_projection = RawProjection::new(perturbed_state_vars, &control_subset);
-            for state_vector in state_vars_projection.iter() {
-                // This should remove all remaining perturbed state variables. Unperturbed
-                // variables should not appear in the control map add all.
-                let control_colors = control_subset.restrict(&state_vector.to_values());
-                let mut map = HashMap::new();
-                for var in &perturbed_variables {
-                    let state_var = graph.as_symbolic_context().get_state_variable(*var);
-                    map.insert(
-                        graph.as_original().get_variable_name(*var).clone(),
-                        state_vector.get_value(state_var).unwrap(),
+        let perturbations = enumerate_perturbations(graph, &control_map, &PerturbationCosts::new());
+        let elapsed = start.elapsed().unwrap();
+        report_best_robustness(perturbation_size, &perturbations, elapsed);
+
+        results.push(BoundedControlResult {
+            perturbation_size,
+            control_map,
+            perturbations,
+            elapsed,
+        });
+    }
+
+    println!(
+        "Sufficient robustness not achieved with perturbation size {}.",
+        size_bound
+    );
+
+    results
+}
+
+/// The same as `bounded_phenotype_control`, but perturbations are bounded by their total
+/// cost (see `PerturbationCosts`) instead of their size.
+///
+/// The result contains all perturbations with cost up to `budget` (inclusive), cheapest first.
+pub fn cost_bounded_phenotype_control(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    costs: &PerturbationCosts,
+    budget: usize,
+) -> Vec<BoundedPerturbation> {
+    let mut results = Vec::new();
+    for cost in 0..(budget + 1) {
+        let start = SystemTime::now();
+        println!("Perturbation cost: {}", cost);
+        let admissible_perturbations = graph.mk_all_perturbations_of_cost(costs, cost);
+        let control_map = phenotype_control_map(
+            graph,
+            phenotype,
+            &admissible_perturbations,
+            &ViolatingTrapSearch { label: cost },
+        );
+        let mut perturbations = enumerate_perturbations(graph, &control_map, costs);
+        report_best_robustness(cost, &perturbations, start.elapsed().unwrap());
+        results.append(&mut perturbations);
+    }
+    results
+}
+
+/// A map which gives us the symbolic variable of the perturbation parameter.
+fn perturbation_var_map(graph: &PerturbationGraph) -> HashMap<VariableId, BddVariable> {
+    graph
+        .variables()
+        .filter_map(|var| graph.get_perturbation_parameter(var).map(|it| (var, it)))
+        .map(|(var, param)| {
+            (
+                var,
+                graph
+                    .as_symbolic_context()
+                    .get_explicit_function_table(param)
+                    .symbolic_variables()[0],
+            )
+        })
+        .collect::<HashMap<_, _>>()
+}
+
+/// Symbolic variables of all perturbation parameters, including perturbable regulations.
+fn all_perturbation_vars(graph: &PerturbationGraph) -> Vec<BddVariable> {
+    let mut result = perturbation_var_map(graph)
+        .values()
+        .cloned()
+        .chain(graph.edge_perturbation_vars().into_iter().map(|(_, it)| it))
+        .collect::<Vec<_>>();
+    result.sort();
+    result
+}
+
+/// Compute the state-color-perturbation combinations within `admissible_perturbations`
+/// that control the network into the `phenotype`.
+///
+/// The `admissible_perturbations` must be closed under the perturbed dynamics (i.e. only
+/// restrict the perturbation parameters and the values of perturbed variables). Only the
+/// attractors reported by the `attractor_backend` are considered violating.
+fn phenotype_control_map(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    admissible_perturbations: &GraphColoredVertices,
+    attractor_backend: &dyn AttractorBackend,
+) -> GraphColoredVertices {
+    let perturbation_var_map = perturbation_var_map(graph);
+
+    let trap = attractor_backend.phenotype_violating_attractors(
+        graph,
+        graph.as_perturbed(),
+        admissible_perturbations,
+        phenotype,
+    );
+
+    let mut inverse_control = trap.into_bdd();
+    for var in graph.variables() {
+        let state_var = graph.as_symbolic_context().get_state_variable(var);
+        if let Some(perturbation_var) = perturbation_var_map.get(&var) {
+            // If the variable can be perturbed, we split into two cases and eliminate
+            // it in the unperturbed cases.
+
+            let is_perturbed = inverse_control.var_select(*perturbation_var, true);
+            let is_not_perturbed = inverse_control
+                .var_select(*perturbation_var, false)
+                .var_exists(state_var);
+            inverse_control = is_perturbed.or(&is_not_perturbed);
+        } else {
+            // If the variable cannot be perturbed, we can eliminate it everywhere.
+            inverse_control = inverse_control.var_exists(state_var);
+        }
+    }
+
+    let inverse_control_map = graph.empty_colored_vertices().copy(inverse_control);
+
+    // Control map consists of admissible state-color pairs that are not in the inverse map.
+    admissible_perturbations.minus(&inverse_control_map)
+}
+
+/// Compute a trap set within `admissible_perturbations` which contains every attractor
+/// outside of the `phenotype` (but possibly also other states).
+fn phenotype_violating_trap(
+    graph: &PerturbationGraph,
+    phenotype: &GraphVertices,
+    admissible_perturbations: &GraphColoredVertices,
+    label: usize,
+) -> GraphColoredVertices {
+    // This is a trap set of all state-color-perturbation combinations that are
+    // guaranteed to stay in the phenotype.
+    let mut trap = admissible_perturbations.intersect_vertices(phenotype);
+
+    'trap: loop {
+        for var in graph.variables().rev() {
+            let can_leave = graph.as_perturbed().var_can_post_out(var, &trap);
+            if !can_leave.is_empty() {
+                trap = trap.minus(&can_leave);
+                if trap.symbolic_size() > 100_000 {
+                    println!(
+                        "[{}] >> Trap phenotype progress: {} / {}",
+                        label,
+                        trap.symbolic_size(),
+                        trap.approx_cardinality()
                     );
                 }
-                let factor = 2.0f64.powi(
-                    (graph.as_symbolic_context().state_variables().len()
-                        + graph.num_perturbation_parameters()) as i32,
-                );
+                continue 'trap;
+            }
+        }
+        break;
+    }
+
+    let mut trap = admissible_perturbations.minus(&trap);
 
-                let working_colors = control_colors.cardinality() / factor;
-                let robustness = working_colors / all_colors_size;
-                if robustness >= best_robustness {
-                    if robustness != best_robustness {
-                        with_best_robustness = 0;
-                    }
-                    best_robustness = robustness;
-                    with_best_robustness += 1;
+    'trap: loop {
+        for var in graph.variables().rev() {
+            let can_leave = graph.as_perturbed().var_can_post_out(var, &trap);
+            if !can_leave.is_empty() {
+                trap = trap.minus(&can_leave);
+                if trap.symbolic_size() > 100_000 {
                     println!(
-                        "[{}] >>>> {:?}: {}; rho = {:.2}",
-                        perturbation_size, map, working_colors, robustness
+                        "[{}] >> Trap non-phenotype progress: {} / {}",
+                        label,
+                        trap.symbolic_size(),
+                        trap.approx_cardinality()
                     );
                 }
+                continue 'trap;
             }
         }
+        break;
+    }
 
-        println!(
-            "[{}] Elapsed: {}ms",
-            perturbation_size,
-            start.elapsed().unwrap().as_millis()
-        );
+    trap
+}
 
-        println!(
-            "[{}] Best robustness {} for {} perturbations.",
-            perturbation_size, best_robustness, with_best_robustness
-        );
+/// The default attractor backend of the bounded phenotype control: every trap set outside
+/// of the phenotype is violating (see `phenotype_violating_trap`).
+///
+/// Every state of the trap set can reach a violating attractor, so the states which can
+/// reach the trap set are exactly the states which can reach a violating attractor.
+struct ViolatingTrapSearch {
+    /// Label of the progress output.
+    label: usize,
+}
 
-        // if best_robustness == 1.0 {
-        //     println!("Sufficient robustness achieved for perturbation size {}.", perturbation_size);
-        //     return;
-        // }
+impl AttractorBackend for ViolatingTrapSearch {
+    fn phenotype_violating_attractors(
+        &self,
+        context: &PerturbationGraph,
+        _graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices {
+        phenotype_violating_trap(context, phenotype, universe, self.label)
     }
+}
+
+/// Enumerate all perturbations in the `control_map`, together with their working colors,
+/// robustness and cost.
+pub(crate) fn enumerate_perturbations(
+    graph: &PerturbationGraph,
+    control_map: &GraphColoredVertices,
+    costs: &PerturbationCosts,
+) -> Vec<BoundedPerturbation> {
+    let perturbation_var_map = perturbation_var_map(graph);
+    let edge_perturbation_vars = graph.edge_perturbation_vars();
+    let bdd_vars = graph.as_symbolic_context().bdd_variable_set();
+    let perturbation_vars = all_perturbation_vars(graph);
+
+    let control_map_bdd = control_map.clone().into_bdd();
+    let perturbation_vars_projection =
+        RawProjection::new(perturbation_vars.clone(), &control_map_bdd);
+
+    let all_colors_size =
+        graph.unit_colors().approx_cardinality() / 2.0f64.powi(perturbation_vars.len() as i32);
+    let mut perturbations = Vec::new();
+
+    for is_perturbed_vector in perturbation_vars_projection.iter() {
+        let perturbed_variables = perturbation_var_map
+            .iter()
+            .filter_map(|(var, p_var)| {
+                if is_perturbed_vector.get_value(*p_var).unwrap() {
+                    Some(*var)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let perturbed_edges = edge_perturbation_vars
+            .iter()
+            .filter(|(_, p_var)| is_perturbed_vector.get_value(*p_var).unwrap())
+            .map(|(edge, _)| *edge)
+            .collect::<Vec<_>>();
+        let perturbed_edge_names = perturbed_edges
+            .iter()
+            .map(|(regulator, target)| {
+                (
+                    graph.as_original().get_variable_name(*regulator),
+                    graph.as_original().get_variable_name(*target),
+                    graph
+                        .get_edge_perturbation_value(*regulator, *target)
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // This should remove all perturbation symbolic variables from the set.
+        let control_subset = control_map_bdd.restrict(&is_perturbed_vector.to_values());
+
+        let perturbed_state_vars = perturbed_variables
+            .iter()
+            .map(|var| graph.as_symbolic_context().get_state_variable(*var))
+            .collect::<Vec<_>>();
+
+        let state_vars_projection = RawProjection::new(perturbed_state_vars, &control_subset);
+        for state_vector in state_vars_projection.iter() {
+            // This should remove all remaining perturbed state variables. Unperturbed
+            // variables should not appear in the control map add all.
+            let control_colors = control_subset.restrict(&state_vector.to_values());
+            let mut values = HashMap::new();
+            for var in &perturbed_variables {
+                let state_var = graph.as_symbolic_context().get_state_variable(*var);
+                values.insert(*var, state_vector.get_value(state_var).unwrap());
+            }
+            let factor = 2.0f64.powi(
+                (graph.as_symbolic_context().state_variables().len() + perturbation_vars.len())
+                    as i32,
+            );
+
+            let working_colors = control_colors.cardinality() / factor;
+            let robustness = working_colors / all_colors_size;
+
+            // The restricted BDD no longer depends on perturbation parameters, so we fix
+            // them back to their values to obtain a valid color set.
+            let working_colors =
+                control_colors.and(&bdd_vars.mk_conjunctive_clause(&is_perturbed_vector));
+            perturbations.push(BoundedPerturbation {
+                perturbation: values
+                    .iter()
+                    .map(|(var, value)| (graph.as_original().get_variable_name(*var), *value))
+                    .collect(),
+                edges: perturbed_edge_names.clone(),
+                cost: costs.total_cost(&values, &perturbed_edges),
+                working_colors: graph.empty_colors().copy(working_colors),
+                robustness,
+            });
+        }
+    }
+
+    perturbations
+}
+
+/// Print every perturbation that matches or improves the best robustness seen so far,
+/// followed by the elapsed time and a summary of the best robustness.
+fn report_best_robustness(label: usize, perturbations: &[BoundedPerturbation], elapsed: Duration) {
+    let mut best_robustness = 0.0;
+    let mut with_best_robustness = 0;
+    for perturbation in perturbations {
+        let robustness = perturbation.robustness;
+        if robustness >= best_robustness {
+            if robustness != best_robustness {
+                with_best_robustness = 0;
+            }
+            best_robustness = robustness;
+            with_best_robustness += 1;
+            println!(
+                "[{}] >>>> {:?}: {}; rho = {:.2}",
+                label,
+                perturbation.perturbation,
+                perturbation.working_colors.approx_cardinality(),
+                robustness
+            );
+        }
+    }
+
+    println!("[{}] Elapsed: {}ms", label, elapsed.as_millis());
 
     println!(
-        "Sufficient robustness not achieved with perturbation size {}.",
-        size_bound
+        "[{}] Best robustness {} for {} perturbations.",
+        label, best_robustness, with_best_robustness
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::aeon::phentoype::build_phenotype;
+    use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::_simplified_algorithm::{
+        all_perturbation_vars, bounded_phenotype_control, bounded_phenotype_control_from,
+        bounded_phenotype_control_with, cost_bounded_phenotype_control,
+    };
+    use crate::phenotype_control::_symbolic_utils::mk_bdd_of_bound;
+    use crate::phenotype_control::{AttractorSearch, PerturbationCosts};
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::traits::Set;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn test_bounded_phenotype_control_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+
+        let results = bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, 1);
+        assert_eq!(2, results.len());
+
+        // Without perturbation, the network does not always end up in the phenotype.
+        assert_eq!(0, results[0].perturbation_size);
+        assert!(results[0].perturbations.is_empty());
+
+        // But the trivial perturbation of the phenotype variable always works.
+        assert_eq!(1, results[1].perturbation_size);
+        let trivial = results[1]
+            .perturbations
+            .iter()
+            .find(|it| it.perturbation == HashMap::from([(String::from("EKLF"), true)]))
+            .unwrap();
+        assert_eq!(1.0, trivial.robustness);
+        assert_eq!(1.0, trivial.working_colors.approx_cardinality());
+        assert!(results[1]
+            .perturbations
+            .iter()
+            .all(|it| it.perturbation.len() == 1 && it.cost == 1));
+    }
+
+    #[test]
+    pub fn test_bounded_phenotype_control_with_sinks_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let phenotype = erythrocyte_phenotype(&perturbations);
+
+        // Only considering fixed points can never reject more perturbations than
+        // considering every trap set outside of the phenotype.
+        let traps = bounded_phenotype_control(&perturbations, &phenotype, 1);
+        let sinks =
+            bounded_phenotype_control_with(&perturbations, &phenotype, 1, &AttractorSearch::Sinks);
+        assert_eq!(traps.len(), sinks.len());
+        for (traps, sinks) in traps.iter().zip(sinks.iter()) {
+            assert!(traps.control_map.is_subset(&sinks.control_map));
+        }
+        assert!(sinks[1]
+            .perturbations
+            .iter()
+            .any(|it| it.perturbation == HashMap::from([(String::from("EKLF"), true)])));
+    }
+
+    #[test]
+    pub fn test_cost_bounded_phenotype_control_myeloid() {
+        let model = myeloid_witness();
+        let perturbations = PerturbationGraph::new(&model);
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+        let eklf = model.as_graph().find_variable("EKLF").unwrap();
+        let eklf_true = HashMap::from([(String::from("EKLF"), true)]);
+
+        // With unit costs, the results are the same as with the size bound.
+        let bounded = bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, 1);
+        let unit_costs = PerturbationCosts::new();
+        let cheapest =
+            cost_bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, &unit_costs, 1);
+        assert_eq!(bounded[1].perturbations.len(), cheapest.len());
+        assert!(cheapest.iter().any(|it| it.perturbation == eklf_true));
+
+        // Once the trivial perturbation is expensive, it is reported after the cheaper ones.
+        let mut costs = PerturbationCosts::new();
+        costs.set_cost(eklf, true, 3);
+        let cheapest =
+            cost_bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, &costs, 2);
+        assert!(!cheapest.is_empty());
+        assert!(cheapest.iter().all(|it| it.perturbation != eklf_true));
+        for pair in cheapest.windows(2) {
+            assert!(pair[0].cost <= pair[1].cost && pair[1].cost <= 2);
+        }
+
+        let cheapest =
+            cost_bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, &costs, 3);
+        let trivial = cheapest
+            .iter()
+            .find(|it| it.perturbation == eklf_true)
+            .unwrap();
+        assert_eq!(3, trivial.cost);
+    }
+
+    #[test]
+    pub fn test_cost_bounded_phenotype_control_edges() {
+        // `B` copies `A`, so `B` is switched off either by a knockout of `A`, or by removing
+        // the regulation of `B` by `A`.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B\n$A: A\n$B: A").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        let perturbations =
+            PerturbationGraph::try_with_edge_perturbations(&model, &[(a, None)], &[(a, b, false)])
+                .unwrap();
+        let phenotype =
+            build_phenotype(perturbations.as_perturbed(), HashMap::from([("B", false)]));
+        let a_false = HashMap::from([(String::from("A"), false)]);
+        let a_b = vec![(String::from("A"), String::from("B"), false)];
+
+        // With unit costs, the regulation costs the same as a variable.
+        let results = bounded_phenotype_control(&perturbations, &phenotype, 1);
+        let edge_only = results[1]
+            .perturbations
+            .iter()
+            .find(|it| it.perturbation.is_empty() && it.edges == a_b)
+            .unwrap();
+        assert_eq!(1, edge_only.cost);
+
+        let mut costs = PerturbationCosts::new();
+        costs.set_edge_cost(a, b, 2);
+        let cheapest = cost_bounded_phenotype_control(&perturbations, &phenotype, &costs, 3);
+        for pair in cheapest.windows(2) {
+            assert!(pair[0].cost <= pair[1].cost);
+        }
+        assert_eq!(a_false, cheapest[0].perturbation);
+        assert!(cheapest[0].edges.is_empty());
+        assert_eq!(1, cheapest[0].cost);
+        let edge_only = cheapest
+            .iter()
+            .find(|it| it.perturbation.is_empty() && it.edges == a_b)
+            .unwrap();
+        assert_eq!(2, edge_only.cost);
+        let both = cheapest
+            .iter()
+            .find(|it| it.perturbation == a_false && it.edges == a_b)
+            .unwrap();
+        assert_eq!(3, both.cost);
+    }
+
+    #[test]
+    pub fn test_bounded_phenotype_control_from_initial_states_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+        let attractors = crate::aeon::attractors::compute(perturbations.as_original());
+        let (inside, outside): (Vec<_>, Vec<_>) = attractors
+            .into_iter()
+            .partition(|it| !it.intersect_vertices(&erythrocyte_phenotype).is_empty());
+
+        // Starting in the erythrocyte attractor, no perturbation is necessary.
+        let results =
+            bounded_phenotype_control_from(&perturbations, &erythrocyte_phenotype, &inside[0], 0);
+        assert_eq!(1, results[0].perturbations.len());
+        assert!(results[0].perturbations[0].perturbation.is_empty());
+        assert_eq!(1.0, results[0].perturbations[0].robustness);
+
+        // From another attractor, we need a perturbation. Some perturbations only work from
+        // this attractor, so we find more of them than when starting anywhere.
+        let results =
+            bounded_phenotype_control_from(&perturbations, &erythrocyte_phenotype, &outside[0], 2);
+        assert!(results[0].perturbations.is_empty());
+        let everywhere = bounded_phenotype_control(&perturbations, &erythrocyte_phenotype, 2);
+        assert!(results[2].perturbations.len() > everywhere[2].perturbations.len());
+        assert!(everywhere[2].control_map.is_subset(&results[2].control_map));
+    }
+
+    #[test]
+    pub fn test_bounded_phenotype_control_from_matches_permanent_control_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let erythrocyte_phenotype = erythrocyte_phenotype(&perturbations);
+        let bdd_vars = perturbations.as_symbolic_context().bdd_variable_set();
+        let perturbation_vars = all_perturbation_vars(&perturbations);
+
+        // Both entry points use the same semantics of initial states.
+        for attractor in crate::aeon::attractors::compute(perturbations.as_original()) {
+            let results = bounded_phenotype_control_from(
+                &perturbations,
+                &erythrocyte_phenotype,
+                &attractor,
+                2,
+            );
+            for result in &results {
+                let of_size =
+                    mk_bdd_of_bound(bdd_vars, &perturbation_vars, result.perturbation_size);
+                let admissible = perturbations
+                    .mk_unit_colors()
+                    .intersect(&perturbations.empty_colors().copy(of_size));
+                let control = perturbations.phenotype_permanent_control_from(
+                    erythrocyte_phenotype.clone(),
+                    admissible,
+                    &attractor,
+                    AttractorSearch::Complex,
+                );
+                assert_eq!(control.as_colored_vertices(), &result.control_map);
+            }
+        }
+    }
+}
@@ -64,9 +64,80 @@ pub fn mk_bdd_of_bound(ctx: &BddVariableSet, variables: &[BddVariable], bound: u
     result
 }
 
+/// A perturbable variable as seen by the cost functions below: the symbolic variable of its
+/// perturbation parameter, its state variable, and the cost of perturbing it to `false`
+/// and to `true`. Perturbable regulations have no state variable and use `cost_true`.
+pub struct CostVariable {
+    pub parameter: BddVariable,
+    pub state: Option<BddVariable>,
+    pub cost_false: usize,
+    pub cost_true: usize,
+}
+
+/// Build a BDD which is true for all valuations of the given `variables` where the total
+/// cost of perturbed variables (with their values) is at most `budget`.
+///
+/// The result depends on the state variables of the perturbed variables, since the cost
+/// can differ for each value.
+pub fn mk_bdd_up_to_cost(ctx: &BddVariableSet, variables: &[CostVariable], budget: usize) -> Bdd {
+    mk_cost_bdd(ctx, variables, budget, false)
+}
+
+/// The same as `mk_bdd_up_to_cost`, but the result only allows valuations with total cost
+/// exactly `cost`.
+pub fn mk_bdd_of_cost(ctx: &BddVariableSet, variables: &[CostVariable], cost: usize) -> Bdd {
+    mk_cost_bdd(ctx, variables, cost, true)
+}
+
+fn mk_cost_bdd(
+    ctx: &BddVariableSet,
+    variables: &[CostVariable],
+    budget: usize,
+    exact: bool,
+) -> Bdd {
+    // `table[b]` holds the valuations of the variables processed so far (from the last one)
+    // with total cost `b` (if `exact`) or at most `b` (otherwise).
+    let mut table = (0..(budget + 1))
+        .map(|b| {
+            if !exact || b == 0 {
+                ctx.mk_true()
+            } else {
+                ctx.mk_false()
+            }
+        })
+        .collect::<Vec<_>>();
+    for var in variables.iter().rev() {
+        let not_perturbed = ctx.mk_literal(var.parameter, false);
+        let (perturbed_false, perturbed_true) = match var.state {
+            Some(state) => (
+                ctx.mk_literal(var.parameter, true)
+                    .and(&ctx.mk_literal(state, false)),
+                ctx.mk_literal(var.parameter, true)
+                    .and(&ctx.mk_literal(state, true)),
+            ),
+            None => (ctx.mk_false(), ctx.mk_literal(var.parameter, true)),
+        };
+        table = (0..(budget + 1))
+            .map(|b| {
+                let mut result = not_perturbed.and(&table[b]);
+                if var.cost_false <= b {
+                    result = result.or(&perturbed_false.and(&table[b - var.cost_false]));
+                }
+                if var.cost_true <= b {
+                    result = result.or(&perturbed_true.and(&table[b - var.cost_true]));
+                }
+                result
+            })
+            .collect();
+    }
+    table.pop().unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::phenotype_control::_symbolic_utils::{mk_bdd_of_bound, mk_bdd_up_to_bound};
+    use crate::phenotype_control::_symbolic_utils::{
+        mk_bdd_of_bound, mk_bdd_of_cost, mk_bdd_up_to_bound, mk_bdd_up_to_cost, CostVariable,
+    };
     use biodivine_lib_bdd::BddVariableSet;
 
     #[test]
@@ -91,6 +162,54 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_cost_bdd() {
+        // Three perturbable variables, each with a parameter and a state variable.
+        let vars = BddVariableSet::new_anonymous(6);
+        let v = vars.variables();
+        let cost_vars = (0..3)
+            .map(|i| CostVariable {
+                parameter: v[2 * i],
+                state: Some(v[2 * i + 1]),
+                cost_false: i + 1,
+                cost_true: 1,
+            })
+            .collect::<Vec<_>>();
+
+        // Nothing perturbed: the three state variables are arbitrary.
+        assert_eq!(8.0, mk_bdd_of_cost(&vars, &cost_vars, 0).cardinality());
+        // One variable perturbed to `true` (3 options), or the first one to `false`.
+        assert_eq!(
+            4.0 * 4.0,
+            mk_bdd_of_cost(&vars, &cost_vars, 1).cardinality()
+        );
+        let up_to_one = mk_bdd_up_to_cost(&vars, &cost_vars, 1);
+        assert_eq!(
+            up_to_one,
+            mk_bdd_of_cost(&vars, &cost_vars, 0).or(&mk_bdd_of_cost(&vars, &cost_vars, 1))
+        );
+
+        // With unit costs, the cost bound is the same as the cardinality bound.
+        let unit_vars = cost_vars
+            .iter()
+            .map(|it| CostVariable {
+                parameter: it.parameter,
+                state: it.state,
+                cost_false: 1,
+                cost_true: 1,
+            })
+            .collect::<Vec<_>>();
+        let parameters = unit_vars.iter().map(|it| it.parameter).collect::<Vec<_>>();
+        assert_eq!(
+            mk_bdd_up_to_bound(&vars, &parameters, 2),
+            mk_bdd_up_to_cost(&vars, &unit_vars, 2)
+        );
+        assert_eq!(
+            mk_bdd_of_bound(&vars, &parameters, 2),
+            mk_bdd_of_cost(&vars, &unit_vars, 2)
+        );
+    }
+
     pub fn binomial(n: usize, k: usize) -> usize {
         factorial(n) / (factorial(k) * factorial(n - k))
     }
@@ -1,12 +1,23 @@
+use crate::control::ControlMode;
 use crate::perturbation::PerturbationGraph;
-use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices, SymbolicAsyncGraph,
+};
+use biodivine_lib_param_bn::VariableId;
+use std::collections::HashMap;
+use std::time::Duration;
 
+pub mod _impl_attractor_search;
+pub mod _impl_phenotype_one_step_control;
 pub mod _impl_phenotype_permanent_control;
+pub mod _impl_phenotype_temporary_control;
 
+mod _impl_perturbation_costs;
 mod _impl_phenotype_control_map;
+mod _impl_serialization;
 
 pub mod _simplified_algorithm;
-mod _symbolic_utils;
+pub(crate) mod _symbolic_utils;
 
 /// A mapping between admissible perturbations and colors for which the perturbation controls
 /// the network.
@@ -17,4 +28,131 @@ mod _symbolic_utils;
 pub struct PhenotypeControlMap {
     context: PerturbationGraph,
     perturbation_set: GraphColoredVertices,
+    semantics: PhenotypeControlSemantics,
+    mode: ControlMode,
+}
+
+/// Decides when a perturbation counts as a phenotype control (see
+/// `PerturbationGraph::phenotype_permanent_control_with_semantics`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PhenotypeControlSemantics {
+    /// The map contains every (perturbed) state and color from which no phenotype-violating
+    /// attractor is reachable. A perturbation may thus only work from some initial states.
+    Permissive,
+    /// The perturbation must work when applied in every state of the network, and every
+    /// resulting state must be able to reach the phenotype.
+    AllInitialStates,
+    /// The same as `AllInitialStates`, but only the given initial states are considered
+    /// (e.g. a known attractor of the unperturbed network). The initial states can differ
+    /// between colors.
+    InitialStates(GraphColoredVertices),
+}
+
+/// The built-in attractor search procedures that can be used by phenotype control.
+///
+/// Each variant is also an `AttractorBackend`. Use
+/// `PerturbationGraph::phenotype_permanent_control_with` to supply a custom backend instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttractorSearch {
+    /// Only consider fixed-point attractors (sinks).
+    Sinks,
+    /// Consider all attractors, including complex (oscillating) ones.
+    Complex,
+    /// Use `Sinks` if the unperturbed network only has fixed-point attractors, and `Complex`
+    /// otherwise. See `HeuristicAttractorSearch`.
+    Heuristic,
+}
+
+/// Decides when an attractor respects a phenotype. This is only relevant for complex
+/// attractors, since a fixed point respects a phenotype iff it lies in it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhenotypeMembership {
+    /// Every state of the attractor lies in the phenotype.
+    StaysIn,
+    /// At least one state of the attractor lies in the phenotype (e.g. for cell-cycle
+    /// models, where the attractor oscillates through the phenotype).
+    Visits,
+    /// At least the given fraction (between `0.0` and `1.0`) of attractor states lies in
+    /// the phenotype. Use `ComplexAttractorSearch::try_new` to validate the fraction.
+    ///
+    /// The fraction is not computed symbolically: the colors of each attractor are split into
+    /// groups with the same attractor states, and each group is counted separately. On
+    /// parametrised models, the number of such groups can be exponential. Hence, attractors
+    /// with more than `MAX_AT_LEAST_COLORS` colors are rejected (with a panic).
+    AtLeast(f64),
+}
+
+/// The largest number of colors of a single attractor that `PhenotypeMembership::AtLeast`
+/// accepts.
+pub const MAX_AT_LEAST_COLORS: f64 = 10_000.0;
+
+/// An `AttractorBackend` which considers all attractors computed by
+/// `aeon::attractors::compute_restricted` (like `AttractorSearch::Complex`), but decides
+/// which of them respect the phenotype based on the given `PhenotypeMembership`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComplexAttractorSearch {
+    membership: PhenotypeMembership,
+}
+
+/// An attractor procedure used by phenotype control to find the attractors which do not
+/// respect a phenotype.
+pub trait AttractorBackend {
+    /// Compute the states of all attractors of `graph` within `universe` which do not
+    /// respect `phenotype`.
+    ///
+    /// Unless stated otherwise by the backend (see `PhenotypeMembership`), an attractor
+    /// respects the phenotype when it is fully contained in it.
+    ///
+    /// The `graph` is one of the graphs of `context` (usually the perturbed graph). The
+    /// `context` can be used to access the other graph (e.g. to make decisions based on
+    /// the unperturbed dynamics).
+    fn phenotype_violating_attractors(
+        &self,
+        context: &PerturbationGraph,
+        graph: &SymbolicAsyncGraph,
+        universe: &GraphColoredVertices,
+        phenotype: &GraphVertices,
+    ) -> GraphColoredVertices;
+}
+
+/// Integer costs of perturbing individual variables to `false` (knockout) or `true`
+/// (overexpression), and of perturbing individual regulations, e.g. drug price or
+/// experimental difficulty.
+///
+/// Perturbations without an explicit cost cost `1`, so the default costs bound the
+/// number of perturbed variables and regulations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PerturbationCosts {
+    costs: HashMap<(VariableId, bool), usize>,
+    edge_costs: HashMap<(VariableId, VariableId), usize>,
+}
+
+/// A single perturbation discovered by `bounded_phenotype_control`, together with the colors
+/// for which it controls the network.
+#[derive(Clone)]
+pub struct BoundedPerturbation {
+    /// Perturbed variables (by name) and the values they are fixed to.
+    pub perturbation: HashMap<String, bool>,
+    /// Perturbed regulations (regulator and target names, and the frozen regulator value),
+    /// see `ControlPerturbation::edges`.
+    pub edges: Vec<(String, String, bool)>,
+    /// The total cost of the perturbation (see `PerturbationCosts`).
+    pub cost: usize,
+    /// The (unperturbed) colors for which the perturbation works.
+    pub working_colors: GraphColors,
+    /// The fraction of all model colors for which the perturbation works.
+    pub robustness: f64,
+}
+
+/// Results of `bounded_phenotype_control` for one perturbation size.
+#[derive(Clone)]
+pub struct BoundedControlResult {
+    /// The number of perturbed variables considered in this result.
+    pub perturbation_size: usize,
+    /// The state-color-perturbation combinations that control the network.
+    pub control_map: GraphColoredVertices,
+    /// All perturbations of this size that work for at least one color.
+    pub perturbations: Vec<BoundedPerturbation>,
+    /// The time it took to compute this result.
+    pub elapsed: Duration,
 }
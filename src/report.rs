@@ -0,0 +1,260 @@
+use crate::control::{ControlMode, ControlPerturbation};
+use crate::error::ControlError;
+use crate::phenotype_control::{BoundedControlResult, BoundedPerturbation};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::time::Duration;
+
+/// A summary of a control computation which can be exported as JSON (see `ControlReport::to_json`).
+#[derive(Clone, Debug)]
+pub struct ControlReport {
+    /// The model file used in the computation.
+    pub model: String,
+    /// The phenotype or target of the control.
+    pub target: String,
+    pub mode: ControlMode,
+    pub perturbations: Vec<ReportedPerturbation>,
+    /// Named durations of the individual steps of the computation.
+    pub timings: Vec<(String, Duration)>,
+}
+
+/// A single perturbation in a `ControlReport`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportedPerturbation {
+    /// Perturbed variables (by name) and the values they are fixed to.
+    pub perturbation: HashMap<String, bool>,
+    /// Perturbed regulations (regulator and target names, and the frozen regulator value),
+    /// see `ControlPerturbation::edges`.
+    pub edges: Vec<(String, String, bool)>,
+    /// The number of (unperturbed) colors for which the perturbation works.
+    pub working_colors: f64,
+    /// The fraction of considered colors for which the perturbation works.
+    pub robustness: f64,
+}
+
+impl ControlReport {
+    pub fn new(model: &str, target: &str, mode: ControlMode) -> ControlReport {
+        ControlReport {
+            model: model.to_string(),
+            target: target.to_string(),
+            mode,
+            perturbations: Vec::new(),
+            timings: Vec::new(),
+        }
+    }
+
+    /// Create a report of `bounded_phenotype_control` results (which use permanent control).
+    pub fn from_bounded_results(
+        model: &str,
+        phenotype: &str,
+        results: &[BoundedControlResult],
+    ) -> ControlReport {
+        let mut report = ControlReport::new(model, phenotype, ControlMode::Permanent);
+        for result in results {
+            report.add_bounded_perturbations(&result.perturbations);
+            report.add_timing(
+                &format!("perturbation size {}", result.perturbation_size),
+                result.elapsed,
+            );
+        }
+        report
+    }
+
+    /// Add perturbations of a `PhenotypeControlMap`, e.g. the result of
+    /// `PhenotypeControlMap::cheapest_perturbations`.
+    pub fn add_bounded_perturbations(&mut self, perturbations: &[BoundedPerturbation]) {
+        for perturbation in perturbations {
+            self.add_perturbation(
+                perturbation.perturbation.clone(),
+                perturbation.edges.clone(),
+                perturbation.working_colors.approx_cardinality(),
+                perturbation.robustness,
+            );
+        }
+    }
+
+    /// Add perturbations of a `ControlMap` together with their robustness, e.g. the result
+    /// of `ControlMap::top_k_by_robustness`.
+    pub fn add_control_perturbations(&mut self, perturbations: &[(ControlPerturbation, f64)]) {
+        for (perturbation, robustness) in perturbations {
+            self.add_perturbation(
+                perturbation.perturbation.clone(),
+                perturbation.edges.clone(),
+                perturbation.working_colors.approx_cardinality(),
+                *robustness,
+            );
+        }
+    }
+
+    pub fn add_perturbation(
+        &mut self,
+        perturbation: HashMap<String, bool>,
+        edges: Vec<(String, String, bool)>,
+        working_colors: f64,
+        robustness: f64,
+    ) {
+        self.perturbations.push(ReportedPerturbation {
+            perturbation,
+            edges,
+            working_colors,
+            robustness,
+        });
+    }
+
+    pub fn add_timing(&mut self, label: &str, duration: Duration) {
+        self.timings.push((label.to_string(), duration));
+    }
+
+    pub fn to_json(&self) -> Value {
+        let perturbations = self
+            .perturbations
+            .iter()
+            .map(|it| {
+                let values = it
+                    .perturbation
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Value::Bool(*value)))
+                    .collect::<Map<_, _>>();
+                let edges = it
+                    .edges
+                    .iter()
+                    .map(|(regulator, target, value)| {
+                        json!({
+                            "regulator": regulator,
+                            "target": target,
+                            "value": value,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "perturbation": values,
+                    "edges": edges,
+                    "size": it.perturbation.len() + it.edges.len(),
+                    "working_colors": it.working_colors,
+                    "robustness": it.robustness,
+                })
+            })
+            .collect::<Vec<_>>();
+        let timings = self
+            .timings
+            .iter()
+            .map(|(label, duration)| (label.clone(), json!(duration.as_millis() as u64)))
+            .collect::<Map<_, _>>();
+        json!({
+            "model": self.model,
+            "target": self.target,
+            "mode": self.mode.to_string(),
+            "perturbations": perturbations,
+            "timings_ms": timings,
+        })
+    }
+
+    /// Write the JSON report into a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ControlError> {
+        let data = serde_json::to_string_pretty(&self.to_json())
+            .map_err(|e| ControlError::Io(e.to_string()))?;
+        std::fs::write(path, data).map_err(|e| ControlError::Io(e.to_string()))
+    }
+}
+
+impl Display for ControlReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Model: {}", self.model)?;
+        writeln!(f, "Target: {}", self.target)?;
+        writeln!(f, "Mode: {}", self.mode)?;
+        for it in &self.perturbations {
+            let mut values = it
+                .perturbation
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, u8::from(*value)))
+                .collect::<Vec<_>>();
+            values.sort();
+            for (regulator, target, value) in &it.edges {
+                values.push(format!("{}={} -> {}", regulator, u8::from(*value), target));
+            }
+            writeln!(
+                f,
+                "[{}]: colors = {}; rho = {:.2}",
+                values.join(", "),
+                it.working_colors,
+                it.robustness
+            )?;
+        }
+        for (label, duration) in &self.timings {
+            writeln!(f, "Elapsed ({}): {}ms", label, duration.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::ControlMode;
+    use crate::perturbation::PerturbationGraph;
+    use crate::phenotype_control::_simplified_algorithm::bounded_phenotype_control;
+    use crate::report::ControlReport;
+    use crate::tests::myeloid_fixtures::{erythrocyte_phenotype, myeloid_witness};
+    use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn test_bounded_control_report_myeloid() {
+        let perturbations = PerturbationGraph::new(&myeloid_witness());
+        let phenotype = erythrocyte_phenotype(&perturbations);
+        let results = bounded_phenotype_control(&perturbations, &phenotype, 1);
+        let report =
+            ControlReport::from_bounded_results("myeloid_witness.aeon", "erythrocyte", &results);
+        let json = report.to_json();
+
+        assert_eq!("myeloid_witness.aeon", json["model"]);
+        assert_eq!("erythrocyte", json["target"]);
+        assert_eq!("permanent", json["mode"]);
+        assert_eq!(2, json["timings_ms"].as_object().unwrap().len());
+        let perturbations = json["perturbations"].as_array().unwrap();
+        assert_eq!(results[1].perturbations.len(), perturbations.len());
+        let trivial = perturbations
+            .iter()
+            .find(|it| it["perturbation"] == serde_json::json!({ "EKLF": true }))
+            .unwrap();
+        assert_eq!(1, trivial["size"]);
+        assert_eq!(1.0, trivial["working_colors"]);
+        assert_eq!(1.0, trivial["robustness"]);
+    }
+
+    #[test]
+    pub fn test_edge_perturbation_report() {
+        // `B` copies `A`, so only the perturbed regulation makes `A` and `B` differ.
+        let model = BooleanNetwork::try_from("A -> A\nA -> B\n$A: A\n$B: A").unwrap();
+        let a = model.as_graph().find_variable("A").unwrap();
+        let b = model.as_graph().find_variable("B").unwrap();
+        let perturbations =
+            PerturbationGraph::try_with_edge_perturbations(&model, &[(a, None)], &[(a, b, false)])
+                .unwrap();
+        let source = ArrayBitVector::from(vec![true, true]);
+        let target = ArrayBitVector::from(vec![true, false]);
+        let control =
+            perturbations.permanent_control(&source, &target, perturbations.unit_colors());
+
+        let mut report = ControlReport::new("edge.aeon", "10", ControlMode::Permanent);
+        let minimal = control
+            .minimal_perturbations()
+            .into_iter()
+            .map(|it| (it, 1.0))
+            .collect::<Vec<_>>();
+        report.add_control_perturbations(&minimal);
+        let json = report.to_json();
+
+        let perturbations = json["perturbations"].as_array().unwrap();
+        assert_eq!(1, perturbations.len());
+        assert_eq!(serde_json::json!({}), perturbations[0]["perturbation"]);
+        assert_eq!(
+            serde_json::json!([{ "regulator": "A", "target": "B", "value": false }]),
+            perturbations[0]["edges"]
+        );
+        assert_eq!(1, perturbations[0]["size"]);
+        assert!(report.to_string().contains("[A=0 -> B]"));
+    }
+}
@@ -1,4 +1,6 @@
 #[cfg(test)]
+pub mod myeloid_fixtures;
+#[cfg(test)]
 pub mod reduced_mapk_test;
 #[cfg(test)]
 pub mod uncertain_mapk_dusp1_test;
@@ -0,0 +1,30 @@
+use crate::aeon::phentoype::build_phenotype;
+use crate::perturbation::PerturbationGraph;
+use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphVertices;
+use biodivine_lib_param_bn::BooleanNetwork;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Load the myeloid witness model (a single color with fixed-point attractors only).
+pub fn myeloid_witness() -> BooleanNetwork {
+    let model_string = &std::fs::read_to_string("models/myeloid_witness.aeon").unwrap();
+    BooleanNetwork::try_from(model_string.as_str()).unwrap()
+}
+
+/// One state of each attractor of the unperturbed network, which serve as the sources and
+/// targets of control.
+pub fn attractor_states(perturbations: &PerturbationGraph) -> Vec<ArrayBitVector> {
+    crate::aeon::attractors::compute(perturbations.as_original())
+        .iter()
+        .map(|it| it.vertices().materialize().iter().next().unwrap())
+        .collect()
+}
+
+/// The erythrocyte phenotype (`EKLF` is active) of the myeloid witness model.
+pub fn erythrocyte_phenotype(perturbations: &PerturbationGraph) -> GraphVertices {
+    build_phenotype(
+        perturbations.as_perturbed(),
+        HashMap::from([("EKLF", true)]),
+    )
+}
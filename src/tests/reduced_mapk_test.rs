@@ -1,13 +1,52 @@
 use rstest::rstest;
 use std::collections::HashMap;
 
-use crate::aeon::config::{get_controllable_vars, get_trivial_phenotype};
+use crate::aeon::config::ExperimentConfig;
 use crate::perturbation::PerturbationGraph;
-use biodivine_lib_param_bn::BooleanNetwork;
+use crate::phenotype_control::AttractorSearch;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphVertices;
+use biodivine_lib_param_bn::{BooleanNetwork, VariableId};
 
 static MAPK_REDUCED_KEY: &str = "reduced_mapk";
 const MAX_CONTROL: usize = 3;
 
+/// Variables of a (variant of the) reduced MAPK model which are not uncontrollable
+/// in the benchmark configuration, nor `extra_forbidden`.
+///
+/// Unlike `ModelConfig::try_controllable_variables`, this keeps the model inputs controllable,
+/// because the expected perturbations (e.g. `v_DNA_damage` or `v_TGFBR_stimulus`) fix them.
+/// Pass the inputs in `extra_forbidden` to exclude them.
+fn get_controllable_vars(
+    model_file: &str,
+    model_name: &str,
+    extra_forbidden: Vec<&str>,
+) -> Vec<VariableId> {
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let model_config = config.try_get_model(model_name).unwrap();
+    let model_string =
+        std::fs::read_to_string(format!("./models_phenotype/{}", model_file)).unwrap();
+    let bn = BooleanNetwork::try_from(model_string.as_str()).unwrap();
+    model_config.try_validate(&bn).unwrap();
+    bn.variables()
+        .filter(|it| {
+            let name = bn.get_variable_name(*it);
+            !model_config.uncontrollable.contains(name) && !extra_forbidden.contains(&name.as_str())
+        })
+        .collect()
+}
+
+fn get_trivial_phenotype(
+    model_name: &str,
+    phenotype_name: &str,
+    stg: &PerturbationGraph,
+) -> GraphVertices {
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let model_config = config.try_get_model(model_name).unwrap();
+    model_config
+        .try_phenotype(phenotype_name, stg.as_perturbed())
+        .unwrap()
+}
+
 // Figure 4C, EGFR over-expression - hardcoded witness model
 // #[rstest]
 // #[case(false, "no_decision", "{}", "sinks")] // r3
@@ -352,7 +391,7 @@ fn colors(#[case] model_file: &str) {
 #[case("[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_FRS2_0000.aeon")]
 #[case("[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_FRS2_0000.aeon")]
 fn mapk_working_apoptosis(#[case] model_file: &str) {
-    let working_perturbations_with_inputs = vec![
+    let working_perturbations_with_inputs = [
         "{\"v_DNA_damage\": true}",
         "{\"v_TGFBR_stimulus\": true}",
         "{\"v_DNA_damage\": true}",
@@ -363,7 +402,7 @@ fn mapk_working_apoptosis(#[case] model_file: &str) {
         "{\"v_FGFR3\": true}",
         "{\"v_TGFBR_stimulus\": true}, {\"v_FGFR3\": true}",
     ];
-    let working_perturbations_without_inputs = vec![
+    let working_perturbations_without_inputs = [
         "{\"v_FRS2\": true}",
         "{\"v_ERK\": false, \"v_EGFR\": true}",
         "{\"v_FRS2\": true, \"v_EGFR\": true}",
@@ -393,7 +432,7 @@ fn mapk_working_apoptosis(#[case] model_file: &str) {
         phenotype_space,
         MAX_CONTROL,
         mapk_reduced_controllable,
-        "complex",
+        AttractorSearch::Complex,
     );
 
     for perturbed_vals in working_perturbations_without_inputs
@@ -418,15 +457,14 @@ fn mapk_working_apoptosis(#[case] model_file: &str) {
     }
 
     // Models without inputs -> perturbations with inputs are admissible
-    if vec![
+    if [
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_0000.aeon",
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_FRS2_0000.aeon",
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_FRS2_0000.aeon",
     ]
-    .iter()
-    .any(|v| v.to_string() == model_file.to_string())
+    .contains(&model_file)
     {
-        for perturbed_vals in working_perturbations_without_inputs.clone() {
+        for perturbed_vals in working_perturbations_without_inputs {
             let perturbation = parse_simple_json_dict(perturbed_vals);
             let working_colors = control_map
                 .perturbation_working_colors(&perturbation)
@@ -457,7 +495,7 @@ fn mapk_working_apoptosis(#[case] model_file: &str) {
 #[case("[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_FRS2_0000.aeon")]
 #[case("[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_FRS2_0000.aeon")]
 fn mapk_working_proliferation(#[case] model_file: &str) {
-    let working_perturbations_without_inputs = vec![
+    let working_perturbations_without_inputs = [
         "{\"v_ERK\": true}",
         "{\"v_p14\": false, \"v_EGFR\": true}",
         "{\"v_p53\": false, \"v_EGFR\": true}",
@@ -489,7 +527,7 @@ fn mapk_working_proliferation(#[case] model_file: &str) {
         phenotype_space,
         MAX_CONTROL,
         mapk_reduced_controllable,
-        "complex",
+        AttractorSearch::Complex,
     );
 
     for perturbed_vals in working_perturbations_without_inputs.iter() {
@@ -511,15 +549,14 @@ fn mapk_working_proliferation(#[case] model_file: &str) {
     }
 
     // Models without inputs -> perturbations with inputs are admissible
-    if vec![
+    if [
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_0000.aeon",
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_FRS2_0000.aeon",
         "[id-089]__[var-13]__[in-4]__[MAPK-REDUCED-1]__unknown_AKT_FRS2_0000.aeon",
     ]
-    .iter()
-    .any(|v| v.to_string() == model_file.to_string())
+    .contains(&model_file)
     {
-        for perturbed_vals in working_perturbations_without_inputs.clone() {
+        for perturbed_vals in working_perturbations_without_inputs {
             let perturbation = parse_simple_json_dict(perturbed_vals);
             let working_colors = control_map
                 .perturbation_working_colors(&perturbation)
@@ -557,7 +594,7 @@ fn mapk_working_no_decision(#[case] model_file: &str) {
         "v_TGFBR_stimulus",
     ];
 
-    let working_perturbations_without_inputs = vec![
+    let working_perturbations_without_inputs = [
         "{}",
         "{\"v_MSK\": false, \"v_EGFR\": true}",
         "{\"v_MSK\": false, \"v_FGFR3\": true}",
@@ -586,7 +623,7 @@ fn mapk_working_no_decision(#[case] model_file: &str) {
         phenotype_space,
         MAX_CONTROL,
         mapk_reduced_controllable,
-        "complex",
+        AttractorSearch::Complex,
     );
 
     for perturbed_vals in working_perturbations_without_inputs.iter() {
@@ -1,7 +1,6 @@
-use biodivine_lib_param_bn::BooleanNetwork;
 use rstest::rstest;
 
-use crate::aeon::config::get_trivial_phenotype;
+use crate::aeon::config::ExperimentConfig;
 use crate::perturbation::PerturbationGraph;
 use crate::phenotype_control::_simplified_algorithm::bounded_phenotype_control;
 
@@ -18,37 +17,17 @@ fn find_all_controls_frs2(#[case] stay: bool, #[case] phenotype: &str, #[case] m
     let max_control_size = 3;
 
     println!(">>>>>>> TEST: {:?} {:?} {:?}", stay, phenotype, model);
-    let config_str = std::fs::read_to_string("./models_phenotype/benchmark.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config_str.as_str()).unwrap();
-    let model_name = config[model]["file"].as_str().unwrap();
-    let model_string =
-        std::fs::read_to_string(format!("./models_phenotype/{}", model_name)).unwrap();
-    let bn = BooleanNetwork::try_from(model_string.as_str()).unwrap();
-    let mut controllable_vars = Vec::new();
-    let uncontrollable = config[model]["uncontrollable"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    let inputs = config[model]["inputs"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    for v in bn.variables() {
-        if !uncontrollable.contains(&bn.get_variable_name(v).as_str())
-            && !inputs.contains(&bn.get_variable_name(v).as_str())
-        {
-            controllable_vars.push(v);
-        }
-    }
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let model_config = config.try_get_model(model).unwrap();
+    let bn = model_config.try_load_network().unwrap();
+    let controllable_vars = model_config.try_controllable_variables(&bn).unwrap();
 
     let perturbation_graph =
         PerturbationGraph::with_restricted_variables(&bn, &(controllable_vars.clone()));
 
-    let mut phenotype_space = get_trivial_phenotype(model, phenotype, &perturbation_graph);
+    let mut phenotype_space = model_config
+        .try_phenotype(phenotype, perturbation_graph.as_perturbed())
+        .unwrap();
     if !stay {
         // Don't stay -> avoid
         phenotype_space = perturbation_graph
@@ -75,37 +54,17 @@ fn find_all_controls_mek1_2(#[case] stay: bool, #[case] phenotype: &str, #[case]
     let max_control_size = 3;
 
     println!(">>>>>>> TEST: {:?} {:?} {:?}", stay, phenotype, model);
-    let config_str = std::fs::read_to_string("./models_phenotype/benchmark.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config_str.as_str()).unwrap();
-    let model_name = config[model]["file"].as_str().unwrap();
-    let model_string =
-        std::fs::read_to_string(format!("./models_phenotype/{}", model_name)).unwrap();
-    let bn = BooleanNetwork::try_from(model_string.as_str()).unwrap();
-    let mut controllable_vars = Vec::new();
-    let uncontrollable = config[model]["uncontrollable"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    let inputs = config[model]["inputs"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    for v in bn.variables() {
-        if !uncontrollable.contains(&bn.get_variable_name(v).as_str())
-            && !inputs.contains(&bn.get_variable_name(v).as_str())
-        {
-            controllable_vars.push(v);
-        }
-    }
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let model_config = config.try_get_model(model).unwrap();
+    let bn = model_config.try_load_network().unwrap();
+    let controllable_vars = model_config.try_controllable_variables(&bn).unwrap();
 
     let perturbation_graph =
         PerturbationGraph::with_restricted_variables(&bn, &(controllable_vars.clone()));
 
-    let mut phenotype_space = get_trivial_phenotype(model, phenotype, &perturbation_graph);
+    let mut phenotype_space = model_config
+        .try_phenotype(phenotype, perturbation_graph.as_perturbed())
+        .unwrap();
     if !stay {
         // Don't stay -> avoid
         phenotype_space = perturbation_graph
@@ -129,37 +88,17 @@ fn find_all_controls_mek_dusp1(#[case] stay: bool, #[case] phenotype: &str, #[ca
     let max_control_size = 3;
 
     println!(">>>>>>> TEST: {:?} {:?} {:?}", stay, phenotype, model);
-    let config_str = std::fs::read_to_string("./models_phenotype/benchmark.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config_str.as_str()).unwrap();
-    let model_name = config[model]["file"].as_str().unwrap();
-    let model_string =
-        std::fs::read_to_string(format!("./models_phenotype/{}", model_name)).unwrap();
-    let bn = BooleanNetwork::try_from(model_string.as_str()).unwrap();
-    let mut controllable_vars = Vec::new();
-    let uncontrollable = config[model]["uncontrollable"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    let inputs = config[model]["inputs"]
-        .as_array()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.as_str().unwrap())
-        .collect::<Vec<&str>>();
-    for v in bn.variables() {
-        if !uncontrollable.contains(&bn.get_variable_name(v).as_str())
-            && !inputs.contains(&bn.get_variable_name(v).as_str())
-        {
-            controllable_vars.push(v);
-        }
-    }
+    let config = ExperimentConfig::try_load("./models_phenotype/benchmark.json").unwrap();
+    let model_config = config.try_get_model(model).unwrap();
+    let bn = model_config.try_load_network().unwrap();
+    let controllable_vars = model_config.try_controllable_variables(&bn).unwrap();
 
     let perturbation_graph =
         PerturbationGraph::with_restricted_variables(&bn, &(controllable_vars.clone()));
 
-    let mut phenotype_space = get_trivial_phenotype(model, phenotype, &perturbation_graph);
+    let mut phenotype_space = model_config
+        .try_phenotype(phenotype, perturbation_graph.as_perturbed())
+        .unwrap();
     if !stay {
         // Don't stay -> avoid
         phenotype_space = perturbation_graph
@@ -0,0 +1,188 @@
+use biodivine_lib_param_bn::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use biodivine_lib_param_bn::BooleanNetwork;
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+use std::process::Command;
+
+/// Run `pbn-control` with the given arguments and parse its standard output as JSON.
+fn pbn_control(args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_pbn-control"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// A state of each attractor of the myeloid witness model, in the format of `--source`.
+fn myeloid_attractor_states() -> Vec<String> {
+    let model_string = &std::fs::read_to_string("models/myeloid_witness.aeon").unwrap();
+    let model = BooleanNetwork::try_from(model_string.as_str()).unwrap();
+    let graph = SymbolicAsyncGraph::new(&model).unwrap();
+    biodivine_pbn_control::aeon::attractors::compute(&graph)
+        .iter()
+        .map(|it| {
+            let state: ArrayBitVector = it.vertices().materialize().iter().next().unwrap();
+            let values = state
+                .values()
+                .into_iter()
+                .map(|it| if it { "True" } else { "False" })
+                .collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        })
+        .collect()
+}
+
+#[test]
+pub fn test_phenotype_json_output() {
+    // Progress of the algorithm must not end up in the standard output.
+    let report = pbn_control(&[
+        "phenotype",
+        "--model",
+        "models/myeloid_witness.aeon",
+        "--phenotype",
+        "EKLF",
+        "--format",
+        "json",
+    ]);
+    assert_eq!("permanent", report["mode"]);
+    let perturbations = report["perturbations"].as_array().unwrap();
+    assert!(perturbations
+        .iter()
+        .any(|it| it["perturbation"] == json!({ "EKLF": true })));
+}
+
+#[test]
+pub fn test_enumerate_saved_map() {
+    // The robustness of an enumerated map is the same as when the map was computed.
+    let states = myeloid_attractor_states();
+    let path = std::env::temp_dir().join("pbn_control_test_cli_control_map.json");
+    let path = path.to_str().unwrap();
+    let control = pbn_control(&[
+        "permanent",
+        "--model",
+        "models/myeloid_4unknown.aeon",
+        "--source",
+        &states[0],
+        "--target",
+        &states[1],
+        "--size-bound",
+        "2",
+        "--save-map",
+        path,
+        "--format",
+        "json",
+    ]);
+    let enumerated = pbn_control(&[
+        "enumerate",
+        "--map",
+        path,
+        "--size-bound",
+        "2",
+        "--format",
+        "json",
+    ]);
+    std::fs::remove_file(path).unwrap();
+
+    let robustness = |report: &Value| {
+        report["perturbations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|it| (it["perturbation"].to_string(), it["robustness"].as_f64()))
+            .collect::<Vec<_>>()
+    };
+    assert!(!robustness(&control).is_empty());
+    // The target is an attractor only in a quarter of the colors, which must not
+    // lower the robustness of the enumerated perturbations.
+    assert_eq!(Some(1.0), robustness(&enumerated)[0].1);
+    assert_eq!(robustness(&control), robustness(&enumerated));
+}
+
+#[test]
+pub fn test_phenotype_one_step_from_source() {
+    // From an attractor outside of the phenotype, a one-step perturbation has to jump
+    // directly into the basin of the erythrocyte attractor.
+    let states = myeloid_attractor_states();
+    let reports = states
+        .iter()
+        .map(|source| {
+            pbn_control(&[
+                "phenotype",
+                "--model",
+                "models/myeloid_witness.aeon",
+                "--phenotype",
+                "EKLF",
+                "--mode",
+                "one-step",
+                "--source",
+                source,
+                "--size-bound",
+                "3",
+                "--format",
+                "json",
+            ])
+        })
+        .collect::<Vec<_>>();
+    assert!(reports.iter().all(|it| it["mode"] == "one-step"));
+    // Only the source in the erythrocyte attractor does not need a perturbation...
+    let needs_no_perturbation = |report: &Value| {
+        report["perturbations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|it| it["perturbation"] == json!({}))
+    };
+    assert_eq!(
+        1,
+        reports
+            .iter()
+            .filter(|it| needs_no_perturbation(it))
+            .count()
+    );
+    // ...but the other attractors can be controlled as well.
+    assert!(reports.iter().any(|it| {
+        !needs_no_perturbation(it) && !it["perturbations"].as_array().unwrap().is_empty()
+    }));
+}
+
+#[test]
+pub fn test_sequential_control() {
+    let states = myeloid_attractor_states();
+    let control = |command: &str, conditions: &[&str]| {
+        let mut args = vec![
+            command,
+            "--model",
+            "models/myeloid_witness.aeon",
+            "--source",
+            &states[0],
+            "--target",
+            &states[1],
+            "--size-bound",
+            "2",
+            "--format",
+            "json",
+        ];
+        for condition in conditions {
+            args.extend(["--condition", condition]);
+        }
+        pbn_control(&args)
+    };
+    // Without conditions, the schedule only has one phase, which is a temporary control.
+    let temporary = control("temporary", &[]);
+    let sequential = control("sequential", &[]);
+    let phases = sequential["phases"].as_array().unwrap();
+    assert_eq!(1, phases.len());
+    assert_eq!("sequential", phases[0]["mode"]);
+    assert_eq!(temporary["perturbations"], phases[0]["perturbations"]);
+
+    let sequential = control("sequential", &["GATA1", "!GATA1"]);
+    let phases = sequential["phases"].as_array().unwrap();
+    assert_eq!(3, phases.len());
+    assert!(phases.iter().all(|it| it["mode"] == "sequential"));
+}